@@ -4,9 +4,12 @@ use {
         entrypoint,
         entrypoint::ProgramResult,
         pubkey::Pubkey,
-        program_error::ProgramError
+        program_error::PrintProgramError
     },
-    crate::processor::Processor
+    crate::{
+        processor::Processor,
+        error::SplitterError
+    }
 };
 
 entrypoint!(process_instruction);
@@ -16,10 +19,9 @@ fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8]
 ) -> ProgramResult {
-    if let Err(_) = Processor::process(program_id, accounts, instruction_data) {
-        return Err(
-            ProgramError::InvalidInstructionData
-        );
+    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
+        error.print::<SplitterError>();
+        return Err(error);
     };
 
     Ok(())
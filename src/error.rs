@@ -1,14 +1,235 @@
-use thiserror::Error;
+use {
+    num_derive::FromPrimitive,
+    solana_program::{
+        decode_error::DecodeError,
+        msg,
+        program_error::{
+            PrintProgramError,
+            ProgramError
+        }
+    },
+    thiserror::Error
+};
 
-#[derive(Error, Debug)]
+/// Errors that may be returned by the splitter program.
+///
+/// Codes are stable, new variants must only be appended.
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
 pub enum SplitterError {
-    #[error("Invalid m parameter. m={0} and expected-accounts-len={1} But provided-accounts-len={2}")]
-    InvalidMParameter(u16, usize, usize)
+    // 0
+    /// The `m` parameter does not match the provided accounts or amounts.
+    #[error("Invalid m parameter")]
+    InvalidMParameter,
+    /// Instruction data could not be decoded.
+    #[error("Invalid instruction")]
+    InvalidInstruction,
+    /// Source account does not hold enough lamports to cover the split.
+    #[error("Insufficient lamports")]
+    InsufficientLamports,
+    /// Source token account does not hold enough tokens to cover the split.
+    #[error("Insufficient tokens")]
+    InsufficientTokens,
+    /// A source or destination token account is frozen.
+    #[error("Token account is frozen")]
+    TokenAccountFrozen,
+
+    // 5
+    /// Account is not a valid token account.
+    #[error("Invalid token account")]
+    InvalidTokenAccount,
+    /// Sum of the requested amounts overflowed.
+    #[error("Amount overflow")]
+    AmountOverflow,
+    /// Number of destination accounts does not match the number of amounts.
+    #[error("Accounts and amounts length mismatch")]
+    AccountsAmountsMismatch,
+    /// Provided system program account is not the system program.
+    #[error("Invalid system program")]
+    InvalidSystemProgram,
+    /// Provided token program account is not the token program.
+    #[error("Invalid token program")]
+    InvalidTokenProgram,
+
+    // 10
+    /// Operator or source account did not sign the transaction.
+    #[error("Missing required signature")]
+    MissingRequiredSignature,
+    /// Source or destination account is not writable.
+    #[error("Account is not writable")]
+    AccountNotWritable,
+    /// A destination account is also used as a source account.
+    #[error("Destination account equals source account")]
+    DestinationIsSource,
+    /// The same destination account appears more than once.
+    #[error("Duplicate destination account")]
+    DuplicateDestination,
+    /// A route references an account index outside of the provided accounts.
+    #[error("Route index out of bounds")]
+    RouteIndexOutOfBounds,
+
+    // 15
+    /// Shares are empty or sum up to zero.
+    #[error("Invalid shares")]
+    InvalidShares,
+    /// Mint account is not a valid mint of the token program.
+    #[error("Invalid mint")]
+    InvalidMint,
+    /// A source or destination token account belongs to a different mint.
+    #[error("Token account not associated with the mint")]
+    MintMismatch,
+    /// Expected decimals differ from the mint's decimals.
+    #[error("Mint decimals mismatch")]
+    MintDecimalsMismatch,
+    /// Gross amount of a net transfer could not be derived from the mint's transfer fee.
+    #[error("Transfer fee calculation failed")]
+    TransferFeeCalculationFailed,
+
+    // 20
+    /// The transfer hook program or its extra-account-metas account was not provided.
+    #[error("Missing transfer hook accounts")]
+    MissingTransferHookAccounts,
+    /// Provided associated token account program account is not the associated token account program.
+    #[error("Invalid associated token account program")]
+    InvalidAssociatedTokenProgram,
+    /// Destination is not the associated token account of the recipient wallet for the mint.
+    #[error("Invalid associated token account")]
+    InvalidAssociatedTokenAccount,
+    /// Operator account is owned by the token program but is not an initialized multisig.
+    #[error("Invalid multisig")]
+    InvalidMultisig,
+    /// The account emptied sources should be closed to was not provided.
+    #[error("Missing close destination account")]
+    MissingCloseDestination,
+
+    // 25
+    /// A lamport destination would not be rent-exempt after the split.
+    #[error("Destination would not be rent-exempt")]
+    DestinationNotRentExempt,
+    /// A guarded account holds less than its minimum before the split.
+    #[error("Starting balance below guard")]
+    StartingBalanceBelowGuard,
+    /// A guarded account would keep less than its minimum after the split.
+    #[error("Remaining balance below guard")]
+    RemainingBalanceBelowGuard,
+    /// The account of a balance guard was not provided.
+    #[error("Missing guard account")]
+    MissingGuardAccount,
+    /// A memo was requested but the memo program account was not provided.
+    #[error("Missing memo program")]
+    MissingMemoProgram,
+
+    // 30
+    /// Number of per-leg memos does not match the number of legs.
+    #[error("Memos and legs length mismatch")]
+    MemoLegsMismatch,
+    /// Config account is not the config PDA or holds invalid data.
+    #[error("Invalid config")]
+    InvalidConfig,
+    /// The config account was not provided.
+    #[error("Missing config account")]
+    MissingConfig,
+    /// The config account is already initialized.
+    #[error("Config already initialized")]
+    ConfigAlreadyInitialized,
+    /// Splits are paused by the admin.
+    #[error("Splits are paused")]
+    SplitsPaused,
+
+    // 35
+    /// Signer is not the admin of the config.
+    #[error("Invalid admin")]
+    InvalidAdmin,
+    /// Fee basis points exceed `MAX_FEE_BASIS_POINTS`.
+    #[error("Invalid fee")]
+    InvalidFee,
+    /// The treasury account or its token account of the mint was not provided.
+    #[error("Missing treasury account")]
+    MissingTreasuryAccount,
+    /// Account is not the program data account of the program.
+    #[error("Invalid program data account")]
+    InvalidProgramData,
+    /// Signer is not the upgrade authority of the program.
+    #[error("Invalid upgrade authority")]
+    InvalidUpgradeAuthority,
+
+    // 40
+    /// No admin was proposed or the signer is not the proposed admin.
+    #[error("Invalid pending admin")]
+    InvalidPendingAdmin,
+    /// No queued config change has the given identifier.
+    #[error("Pending change not found")]
+    PendingChangeNotFound,
+    /// The timelock of the queued config change has not elapsed yet.
+    #[error("Timelock not elapsed")]
+    TimelockNotElapsed,
+    /// `MAX_PENDING_CHANGES` config changes are already queued.
+    #[error("Too many pending changes")]
+    TooManyPendingChanges,
+    /// A policy account was not provided.
+    #[error("Missing policy account")]
+    MissingPolicy,
+
+    // 45
+    /// Policy account is not the policy PDA of its kind or holds invalid data.
+    #[error("Invalid policy")]
+    InvalidPolicy,
+    /// `MAX_POLICY_ADDRESSES` addresses are already listed.
+    #[error("Policy is full")]
+    PolicyFull,
+    /// A mint of the split is not on the mint allowlist.
+    #[error("Mint not allowed")]
+    MintNotAllowed,
+    /// A recipient of the split is on the recipient denylist.
+    #[error("Recipient denied")]
+    RecipientDenied,
+    /// Split plan account is not a plan PDA of the program or holds invalid data.
+    #[error("Invalid split plan")]
+    InvalidPlan,
+
+    // 50
+    /// Signer is not the authority of the split plan.
+    #[error("Invalid split plan authority")]
+    InvalidPlanAuthority,
+    /// The split plan has no recipients or more than `MAX_PLAN_RECIPIENTS`.
+    #[error("Invalid number of split plan recipients")]
+    InvalidPlanRecipients,
+    /// Destination accounts do not match the recipients of the split plan.
+    #[error("Destinations do not match the split plan")]
+    PlanDestinationMismatch,
+    /// The split options given to `ExecutePlan` are not those of the asset of the plan.
+    #[error("Split options do not match the split plan")]
+    PlanOptionsMismatch,
+    /// The split plan is locked and can no longer be updated nor closed.
+    #[error("Split plan is locked")]
+    PlanLocked,
+
+    // 55
+    /// The split plan is not at the version the execution expects.
+    #[error("Split plan version mismatch")]
+    PlanVersionMismatch
 }
-impl Into<u32> for SplitterError {
-    fn into(self) -> u32 {
-        match self {
-            Self::InvalidMParameter(_, _ , _) => 0u32
-        }
+
+impl From<SplitterError> for ProgramError {
+    fn from(e: SplitterError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for SplitterError {
+    fn type_of() -> &'static str {
+        "SplitterError"
     }
-}
\ No newline at end of file
+}
+
+impl PrintProgramError for SplitterError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -0,0 +1,149 @@
+use {
+    base64::{
+        engine::general_purpose::STANDARD,
+        Engine
+    },
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize
+    },
+    solana_program::{
+        entrypoint::ProgramResult,
+        log::sol_log_data,
+        program_error::ProgramError,
+        pubkey::Pubkey
+    },
+    crate::instruction::{
+        AssetTotal,
+        SplitKind,
+        SplitterInstruction
+    }
+};
+
+/// Prefix of the transaction log lines `sol_log_data` writes to
+pub const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Header event emitted once by every successful split, before its `TransferLeg` events
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct SplitExecuted {
+    pub kind: SplitKind,
+    /// Number of `TransferLeg` events that follow
+    pub legs: u32,
+    /// Total moved to the recipients per asset, in order of first transfer
+    pub totals: Vec<AssetTotal>,
+    /// SHA-256 of the destination addresses concatenated in transfer order
+    pub recipients_hash: [u8; 32],
+    /// Protocol fee sent to the treasury per asset, its transfers have no `TransferLeg`
+    pub fees: Vec<AssetTotal>
+}
+
+/// Event emitted for every transfer made by a split
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct TransferLeg {
+    /// Position of the transfer in the split
+    pub index: u32,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    /// Mint of the tokens, `None` for lamports
+    pub mint: Option<Pubkey>,
+    /// Lamports or raw token amount moved out of the source, transfer fee included
+    pub amount: u64
+}
+
+/// Events emitted by the splitter program
+///
+/// Every event is logged through `sol_log_data` as a single field made of an 8-byte
+/// discriminator, derived like the instruction ones from `event:<lowercasename>`,
+/// followed by the borsh-serialized event
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitterEvent {
+    SplitExecuted(SplitExecuted),
+    TransferLeg(TransferLeg)
+}
+
+impl SplitterEvent {
+    pub fn unpack(event_data: &[u8]) -> Result<Self, ProgramError> {
+        if event_data.len() < 8 {
+            return Err(
+                ProgramError::InvalidAccountData
+            );
+        };
+
+        let (
+            ev_splitexecuted,
+            ev_transferleg
+        ) = (
+            SplitterInstruction::get_discriminator("event:splitexecuted"),
+            SplitterInstruction::get_discriminator("event:transferleg")
+        );
+
+        let (ev_identifier, data) = event_data.split_at(8);
+        if ev_identifier == ev_splitexecuted {
+            Ok(
+                Self::SplitExecuted(
+                    Self::deserialize::<SplitExecuted>(data)?
+                )
+            )
+        } else if ev_identifier == ev_transferleg {
+            Ok(
+                Self::TransferLeg(
+                    Self::deserialize::<TransferLeg>(data)?
+                )
+            )
+        } else {
+            Err(
+                ProgramError::InvalidAccountData
+            )
+        }
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let (discriminator, data) = match self {
+            Self::SplitExecuted(event) => (
+                SplitterInstruction::get_discriminator("event:splitexecuted"),
+                Self::serialize(event)?
+            ),
+            Self::TransferLeg(event) => (
+                SplitterInstruction::get_discriminator("event:transferleg"),
+                Self::serialize(event)?
+            )
+        };
+
+        let mut buf = Vec::with_capacity(8 + data.len());
+        buf.extend_from_slice(&discriminator);
+        buf.extend_from_slice(&data);
+
+        Ok(buf)
+    }
+
+    /// Decodes an event from a `Program data: <base64>` transaction log line, `None` when the
+    /// line is not a splitter event.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let encoded = log.strip_prefix(PROGRAM_DATA_LOG_PREFIX)?;
+        let event_data = STANDARD.decode(encoded).ok()?;
+
+        Self::unpack(&event_data).ok()
+    }
+
+    /// Decodes every splitter event of the transaction logs, in emission order.
+    pub fn from_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        logs
+            .iter()
+            .filter_map(|log| Self::from_log(log.as_ref()))
+            .collect()
+    }
+
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.pack()?]);
+
+        Ok(())
+    }
+
+    fn deserialize<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+        T::try_from_slice(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    fn serialize<T: BorshSerialize>(data: &T) -> Result<Vec<u8>, ProgramError> {
+        data.try_to_vec().map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
@@ -6,27 +6,392 @@ use {
         BorshSerialize
     },
     solana_program::{
+        instruction::{
+            AccountMeta,
+            Instruction
+        },
         program_error::ProgramError,
-        hash::hash
+        pubkey::Pubkey,
+        hash::hash,
+        program::get_return_data,
+        system_program
+    },
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    crate::{
+        error::SplitterError,
+        state::{
+            get_config_address,
+            get_plan_address,
+            get_policy_address,
+            get_program_data_address,
+            ConfigChange,
+            PlanAllocation,
+            PlanAsset,
+            PolicyKind,
+            SplitPlan
+        }
     }
 };
 
+/// Balance requirements on an account of a split
+///
+/// Balances are raw token amounts for token accounts and lamports for any other account
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct BalanceGuard {
+    /// Guarded account, usually a source, it must be one of the instruction's accounts
+    pub account: Pubkey,
+    /// The account must hold at least this much before the split
+    pub min_before: Option<u64>,
+    /// The account must keep at least this much after the split
+    pub min_after: Option<u64>
+}
+
+/// Memo logged through the SPL Memo program right before the transfers of a split
+///
+/// The memo program must be passed after the other accounts
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum SplitMemo {
+    /// The same memo before every transfer
+    Batch(String),
+    /// One memo per amount, share or route, an empty memo leaves its transfer without one
+    PerLeg(Vec<String>)
+}
+
+/// Options shared by every token split instruction
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default, PartialEq)]
+pub struct TokenSplitOptions {
+    /// Amounts are what the recipients must receive, they are grossed up by the
+    /// mint's current `TransferFeeConfig` and sent with `transfer_checked_with_fee`
+    pub net_amounts: bool,
+    /// Source token accounts left empty are closed and their rent is sent to this
+    /// account, which must be passed after the other accounts. Wrapped SOL sources are
+    /// unwrapped the same way. The operator must be the owner or close authority
+    pub close_sources_to: Option<Pubkey>,
+    /// Checked before and after the split
+    pub guards: Vec<BalanceGuard>,
+    /// Logged before every transfer, also satisfies destinations requiring memos on
+    /// incoming transfers
+    pub memo: Option<SplitMemo>
+}
+
+/// What a lamport split does with a destination that would not be rent-exempt
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default, PartialEq)]
+pub enum RentExemptionPolicy {
+    /// Fail the whole split with `DestinationNotRentExempt`
+    #[default]
+    Fail,
+    /// Leave the destination out of the split
+    Skip,
+    /// Raise the amount to what makes the destination rent-exempt
+    TopUp
+}
+
+/// Options shared by the lamport split instructions
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default, PartialEq)]
+pub struct LamportSplitOptions {
+    /// Applied to every destination whose balance after the split would be below
+    /// `Rent::minimum_balance` of its data length
+    pub rent_exemption: RentExemptionPolicy,
+    /// Checked before and after the split
+    pub guards: Vec<BalanceGuard>,
+    /// Logged before every transfer
+    pub memo: Option<SplitMemo>
+}
+
+/// Options of the split a plan runs, matching the asset of the plan
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum PlanSplitOptions {
+    /// Options of a plan of lamports
+    Lamports(LamportSplitOptions),
+    /// Options of a plan of tokens
+    Token(TokenSplitOptions)
+}
+
+impl PlanSplitOptions {
+    pub fn guards(&self) -> &[BalanceGuard] {
+        match self {
+            Self::Lamports(options) => &options.guards,
+            Self::Token(options) => &options.guards
+        }
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct SplitLamports {
-    pub amounts: Vec<u64>
+    pub amounts: Vec<u64>,
+    pub options: LamportSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitLamportsByShares {
+    pub total: u64,
+    pub shares: Vec<u64>,
+    pub options: LamportSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitLamportsToWrappedSol {
+    pub amounts: Vec<u64>,
+    pub options: LamportSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitWrappedSolToLamports {
+    pub amounts: Vec<u64>,
+    pub options: LamportSplitOptions
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct SplitSplTokensFromSingleMint {
-    pub amounts: Vec<u64>
+    pub amounts: Vec<u64>,
+    pub decimals: u8,
+    pub options: TokenSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitSplTokensFromSingleMintByShares {
+    pub total: u64,
+    pub shares: Vec<u64>,
+    pub sweep: bool,
+    pub decimals: u8,
+    pub options: TokenSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitSplTokensFromSingleMintToWallets {
+    pub amounts: Vec<u64>,
+    pub decimals: u8,
+    pub options: TokenSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitSplTokensFromSingleMintBySharesToWallets {
+    pub total: u64,
+    pub shares: Vec<u64>,
+    pub sweep: bool,
+    pub decimals: u8,
+    pub options: TokenSplitOptions
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct SplitSplTokensFromMultipleMints {
     pub amounts: Vec<u64>,
-    pub m: u16
+    pub m: u16,
+    pub decimals: Vec<u8>,
+    pub options: TokenSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct TransferRoute {
+    /// Index of the source token account in the remaining accounts
+    pub source_index: u16,
+    /// Index of the destination token account in the remaining accounts
+    pub destination_index: u16,
+    /// Index of the mint account in the remaining accounts
+    pub mint_index: u16,
+    pub amount: u64,
+    /// Expected decimals of the mint
+    pub decimals: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitSplTokensByRoutes {
+    pub routes: Vec<TransferRoute>,
+    pub options: TokenSplitOptions
+}
+
+/// A single payment of a mixed-asset split
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum SplitLeg {
+    /// Lamports paid by the operator to the destination account
+    Lamports {
+        /// Index of the destination account in the leg accounts
+        destination_index: u16,
+        amount: u64
+    },
+    /// Tokens moved along a route, the indexes point into the leg accounts
+    Token(TransferRoute)
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SplitMixedAssets {
+    pub legs: Vec<SplitLeg>,
+    pub options: TokenSplitOptions
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct QuoteSplit {
+    pub total: u64,
+    pub shares: Vec<u64>,
+    pub sweep: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct InitializeConfig {
+    pub admin: Pubkey,
+    pub fee_basis_points: u16,
+    pub treasury: Pubkey,
+    pub timelock_delay: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ProposeAdmin {
+    pub admin: Option<Pubkey>
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AcceptAdmin {}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct QueueConfigChange {
+    pub change: ConfigChange
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ExecuteConfigChange {
+    pub id: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CancelConfigChange {
+    pub id: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SetPaused {
+    pub paused: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct InitializePolicy {
+    pub kind: PolicyKind
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AddPolicyAddresses {
+    pub kind: PolicyKind,
+    pub addresses: Vec<Pubkey>
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RemovePolicyAddresses {
+    pub kind: PolicyKind,
+    pub addresses: Vec<Pubkey>
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ClosePolicy {
+    pub kind: PolicyKind
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CreatePlan {
+    pub id: u64,
+    pub asset: PlanAsset,
+    pub recipients: Vec<Pubkey>,
+    pub allocation: PlanAllocation
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UpdatePlan {
+    pub recipients: Vec<Pubkey>,
+    pub allocation: PlanAllocation
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ClosePlan {}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LockPlan {}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ExecutePlan {
+    pub total: u64,
+    pub expected_version: u64,
+    pub options: PlanSplitOptions
+}
+
+/// Return data of `QuoteSplit`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct SplitQuote {
+    /// Amount each recipient receives, in the order of the shares, net of the protocol fee
+    pub amounts: Vec<u64>,
+    /// Amount split between the recipients, protocol fee included
+    pub total: u64,
+    /// Part of the total sent to the treasury as protocol fee
+    pub fee: u64
 }
 
+/// Split instruction a `SplitSummary` was returned by
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum SplitKind {
+    SplitLamports,
+    SplitSplTokensFromSingleMint,
+    SplitSplTokensFromMultipleMints,
+    SplitSplTokensByRoutes,
+    SplitLamportsByShares,
+    SplitSplTokensFromSingleMintByShares,
+    SplitSplTokensFromSingleMintToWallets,
+    SplitSplTokensFromSingleMintBySharesToWallets,
+    SplitLamportsToWrappedSol,
+    SplitWrappedSolToLamports,
+    SplitMixedAssets,
+    ExecutePlan
+}
+
+/// Amount of a single asset moved by a split
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct AssetTotal {
+    /// Mint of the tokens, `None` for lamports
+    pub mint: Option<Pubkey>,
+    /// Lamports or raw token amount moved out of the sources, transfer fees included
+    pub amount: u64
+}
+
+/// Return data of every successful split
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct SplitSummary {
+    pub kind: SplitKind,
+    /// Number of transfers made, destinations skipped by the rent-exemption policy excluded
+    pub legs: u32,
+    /// Total moved to the recipients per asset, in order of first transfer
+    pub totals: Vec<AssetTotal>,
+    /// SHA-256 of the destination addresses concatenated in transfer order
+    pub recipients_hash: [u8; 32],
+    /// Protocol fee sent to the treasury per asset
+    ///
+    /// The fee transfers are not part of the legs, the totals nor the recipients hash, so
+    /// splits to the same recipients hash alike whatever the fee
+    pub fees: Vec<AssetTotal>
+}
+
+impl SplitSummary {
+    /// Decodes a summary from the return data of a split.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Decodes the summary left by the last split invoked through CPI, `None` when the
+    /// return data was not set by `program_id`.
+    pub fn from_return_data(program_id: &Pubkey) -> Option<Self> {
+        let (return_program_id, data) = get_return_data()?;
+        if return_program_id != *program_id {
+            return None;
+        };
+
+        Self::unpack(&data).ok()
+    }
+}
+
+/// Instructions supported by the splitter program
+/// 
+/// Every successful split sets a borsh-serialized `SplitSummary` as return data
+/// 
+/// Every split also expects the config PDA, then the mint allowlist and recipient denylist
+/// policy PDAs, after its other accounts. A policy whose account is not initialized is not
+/// enforced. When the config charges a fee, the `[writable]` treasury is expected there too
+/// for lamport transfers, and its `[writable]` associated token account of each mint for
+/// token transfers
 #[derive(Debug, PartialEq)]
 pub enum SplitterInstruction {
     /// Splits specified lamports to the desired addresses
@@ -38,91 +403,2013 @@ pub enum SplitterInstruction {
     ///     0. `[writable,signer]` system-program owned account as source account
     ///     1. `[]` system-program
     ///     2. 2..2+M `[writable]` M system-program owned accounts as destination accounts
-    SplitLamports(Vec<u64>),
+    SplitLamports(
+        Vec<u64>,
+        LamportSplitOptions
+    ),
     /// Splits specified spl tokens to the desired token accounts
     /// 
-    /// Amoutns must be raw amounts
+    /// Amounts must be raw amounts, every transfer is checked against the mint and
+    /// the expected `decimals`
     /// 
     /// Accounts expected by this instruction:
     /// 
-    ///     0. `[signer]` owner/delegator of the source token account
+    ///     0. `[signer]` owner/delegator of the source token account, `[]` when it is an SPL multisig
     ///     1. `[]` token-standard-program
-    ///     2. `[writable]` source token account
-    ///     3. 3..3+M `[writable]` M destination token accounts
-    SplitSplTokensFromSingleMint(Vec<u64>),
+    ///     2. `[]` mint account
+    ///     3. `[writable]` source token account
+    ///     4. 4..4+M `[writable]` M destination token accounts
+    ///     5. 4+M.. `[signer]` signers of a multisig operator and the extra accounts required by the mint's transfer hook, if any
+    SplitSplTokensFromSingleMint(
+        Vec<u64>,
+        u8,
+        TokenSplitOptions
+    ),
     /// Splits specified spl tokens to the desired token accounts
     /// 
-    /// Amoutns must be raw amounts
+    /// Amounts must be raw amounts, every transfer is checked against the mint and
+    /// the expected decimals of its pair
     /// 
     /// Accounts expected by this instruction:
     /// 
     ///     * This instruction requires a `m` parameter to be passed as data(u16)
-    ///     0. `[signer]` owner/delegator of the source token account
+    ///     0. `[signer]` owner/delegator of the source token account, `[]` when it is an SPL multisig
     ///     1. `[]` token-standard-program
     ///     2. 2..2+m `[writable]` m source accounts
-    ///     3. m+2.. `[wrtiable]` m destination accounts
+    ///     3. m+2..2m+2 `[writable]` m destination accounts
+    ///     4. 2m+2..3m+2 `[]` m mint accounts
+    ///     5. 3m+2.. `[signer]` signers of a multisig operator and the extra accounts required by the mints' transfer hooks, if any
     SplitSplTokensFromMultipleMints(
         Vec<u64>,
-        u16
+        u16,
+        Vec<u8>,
+        TokenSplitOptions
+    ),
+    /// Transfers spl tokens along an explicit routing table
+    /// 
+    /// Each route is a `(source_index, destination_index, mint_index, amount, decimals)`
+    /// leg where the indexes point into the remaining accounts, so one source can fan
+    /// out to many destinations and several mints can be split in the same instruction
+    /// 
+    /// Amounts must be raw amounts
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` owner/delegator of the source token accounts, `[]` when it is an SPL multisig
+    ///     1. `[]` token-standard-program
+    ///     2. 2.. remaining accounts, `[writable]` source and destination token accounts and `[]` mint accounts referenced by the routes,
+    ///        followed by the `[signer]` signers of a multisig operator and the extra accounts required by the mints' transfer hooks, if any
+    SplitSplTokensByRoutes(
+        Vec<TransferRoute>,
+        TokenSplitOptions
+    ),
+    /// Splits `total` lamports between the destination addresses by weight
+    /// 
+    /// Shares are relative weights, e.g. basis points summing to 10_000. Every
+    /// destination receives `floor(total * share / sum(shares))` and the lamports lost
+    /// to rounding are handed out one by one to the destinations with the largest
+    /// truncated remainder, ties going to the earlier destination, so exactly `total`
+    /// lamports are moved
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` system-program owned account as source account
+    ///     1. `[]` system-program
+    ///     2. 2..2+M `[writable]` M system-program owned accounts as destination accounts
+    SplitLamportsByShares(
+        u64,
+        Vec<u64>,
+        LamportSplitOptions
+    ),
+    /// Splits `total` raw tokens between the destination token accounts by weight
+    /// 
+    /// When `sweep` is set `total` is ignored and the whole balance the source token
+    /// account holds at execution time is split instead. Allocations follow the same
+    /// rounding rule as `SplitLamportsByShares`
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` owner/delegator of the source token account, `[]` when it is an SPL multisig
+    ///     1. `[]` token-standard-program
+    ///     2. `[]` mint account
+    ///     3. `[writable]` source token account
+    ///     4. 4..4+M `[writable]` M destination token accounts
+    ///     5. 4+M.. `[signer]` signers of a multisig operator and the extra accounts required by the mint's transfer hook, if any
+    SplitSplTokensFromSingleMintByShares(
+        u64,
+        Vec<u64>,
+        bool,
+        u8,
+        TokenSplitOptions
+    ),
+    /// Same as `SplitSplTokensFromSingleMint` but pays recipient wallets
+    /// 
+    /// The associated token account of every wallet is created idempotently through the
+    /// associated-token-account program at the rent payer's expense, destinations that
+    /// already exist must be the canonical associated token account of the wallet
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` owner/delegator of the source token account, `[]` when it is an SPL multisig
+    ///     1. `[]` token-standard-program
+    ///     2. `[]` mint account
+    ///     3. `[writable]` source token account
+    ///     4. `[writable,signer]` rent payer of the created token accounts
+    ///     5. `[]` system-program
+    ///     6. `[]` associated-token-account-program
+    ///     7. 7..7+M `[]` M recipient wallets
+    ///     8. 7+M..7+2M `[writable]` M associated token accounts of the recipient wallets
+    ///     9. 7+2M.. `[signer]` signers of a multisig operator and the extra accounts required by the mint's transfer hook, if any
+    SplitSplTokensFromSingleMintToWallets(
+        Vec<u64>,
+        u8,
+        TokenSplitOptions
+    ),
+    /// Same as `SplitSplTokensFromSingleMintByShares` but pays recipient wallets, see
+    /// `SplitSplTokensFromSingleMintToWallets`
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` owner/delegator of the source token account, `[]` when it is an SPL multisig
+    ///     1. `[]` token-standard-program
+    ///     2. `[]` mint account
+    ///     3. `[writable]` source token account
+    ///     4. `[writable,signer]` rent payer of the created token accounts
+    ///     5. `[]` system-program
+    ///     6. `[]` associated-token-account-program
+    ///     7. 7..7+M `[]` M recipient wallets
+    ///     8. 7+M..7+2M `[writable]` M associated token accounts of the recipient wallets
+    ///     9. 7+2M.. `[signer]` signers of a multisig operator and the extra accounts required by the mint's transfer hook, if any
+    SplitSplTokensFromSingleMintBySharesToWallets(
+        u64,
+        Vec<u64>,
+        bool,
+        u8,
+        TokenSplitOptions
+    ),
+    /// Splits specified lamports to the desired native-mint token accounts as wrapped SOL
+    /// 
+    /// Lamports are transferred like `SplitLamports` and every destination is synced
+    /// with `sync_native` afterwards
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` system-program owned account as source account
+    ///     1. `[]` system-program
+    ///     2. `[]` token-standard-program
+    ///     3. 3..3+M `[writable]` M native-mint token accounts as destination accounts
+    SplitLamportsToWrappedSol(
+        Vec<u64>,
+        LamportSplitOptions
+    ),
+    /// Splits wrapped SOL of a native-mint token account to the desired addresses as lamports
+    /// 
+    /// The sum of the amounts is moved to the scratch native-mint token account like
+    /// `SplitSplTokensFromSingleMint`, the scratch account is closed to the operator and
+    /// the unwrapped lamports are split from the operator like `SplitLamports`. The
+    /// scratch account's rent is returned to the operator
+    /// 
+    /// The scratch account must already exist and be owned by the operator. It is
+    /// closed on every call, so it has to be created again before the next one. The operator
+    /// must be a single signer, SPL multisig operators are not supported
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` system-program owned owner/delegator of the source token account
+    ///     1. `[]` system-program
+    ///     2. `[]` token-standard-program
+    ///     3. `[]` native mint account
+    ///     4. `[writable]` source native-mint token account
+    ///     5. `[writable]` scratch native-mint token account owned by the operator
+    ///     6. 6..6+M `[writable]` M system-program owned accounts as destination accounts
+    SplitWrappedSolToLamports(
+        Vec<u64>,
+        LamportSplitOptions
+    ),
+    /// Pays lamports and tokens of any number of mints in a single atomic instruction
+    /// 
+    /// Every leg is either a `Lamports` payment from the operator or a `Token` route like
+    /// in `SplitSplTokensByRoutes`, the indexes of both point into the leg accounts
+    /// 
+    /// Lamport legs are paid by the operator itself, which must then be a single signer. An
+    /// SPL multisig operator can only be used when every leg is a `Token` route
+    /// 
+    /// Amounts must be in lamport or raw amounts
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` owner/delegator of the source token accounts, `[writable,signer]` system-program owned source of the lamports when there are lamport legs
+    ///     1. `[]` token-standard-program
+    ///     2. `[]` system-program
+    ///     3. 3.. leg accounts, `[writable]` lamport destinations, source and destination token accounts and `[]` mint accounts referenced by the legs,
+    ///        followed by the `[signer]` signers of a multisig operator and the extra accounts required by the mints' transfer hooks, if any
+    SplitMixedAssets(
+        Vec<SplitLeg>,
+        TokenSplitOptions
+    ),
+    /// Computes the allocations of a split by shares without transferring anything
+    /// 
+    /// The allocations are computed like in `SplitLamportsByShares` and
+    /// `SplitSplTokensFromSingleMintByShares`, the protocol fee of the config deducted, and
+    /// returned as a borsh-serialized `SplitQuote` through the return data, read it by
+    /// simulating the transaction
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[]` token-standard-program, only when sweeping
+    ///     1. `[]` mint account, only when sweeping
+    ///     2. `[]` source token account whose whole balance is split, only when sweeping
+    ///     3. `[]` config PDA
+    QuoteSplit(
+        u64,
+        Vec<u64>,
+        bool
+    ),
+    /// Creates the config PDA with the admin, the protocol fee, the treasury and the
+    /// timelock delay in seconds, splits are not paused
+    /// 
+    /// Only the upgrade authority of the program can initialize the config
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` payer of the config account
+    ///     1. `[signer]` upgrade authority of the program
+    ///     2. `[]` program data account of the program
+    ///     3. `[writable]` config PDA
+    ///     4. `[]` system-program
+    InitializeConfig(
+        Pubkey,
+        u16,
+        Pubkey,
+        u32
+    ),
+    /// Proposes the next admin of the config, `None` withdraws the proposal
+    /// 
+    /// Accounts expected by this instruction and the other config updates:
+    /// 
+    ///     0. `[signer]` admin
+    ///     1. `[writable]` config PDA
+    ProposeAdmin(Option<Pubkey>),
+    /// Hands the config over to the proposed admin
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` proposed admin
+    ///     1. `[writable]` config PDA
+    AcceptAdmin,
+    /// Queues a config change, executable once the timelock delay has elapsed
+    QueueConfigChange(ConfigChange),
+    /// Applies the queued config change with the given identifier
+    ExecuteConfigChange(u64),
+    /// Drops the queued config change with the given identifier
+    CancelConfigChange(u64),
+    /// Pauses or resumes every split, takes effect immediately
+    SetPaused(bool),
+    /// Creates the empty policy PDA of the kind, enforced from then on
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` payer of the policy account
+    ///     1. `[signer]` admin
+    ///     2. `[]` config PDA
+    ///     3. `[writable]` policy PDA
+    ///     4. `[]` system-program
+    InitializePolicy(PolicyKind),
+    /// Lists the addresses in the policy of the kind, the policy account grows by 32 bytes
+    /// per new address and the payer tops up its rent
+    /// 
+    /// A policy lists at most `MAX_POLICY_ADDRESSES` addresses, the instruction fails with
+    /// `PolicyFull` beyond. An instruction can grow the account by `MAX_PERMITTED_DATA_INCREASE`
+    /// only, that is 320 addresses, so long lists are added over several instructions
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` admin
+    ///     1. `[]` config PDA
+    ///     2. `[writable]` policy PDA
+    ///     3. `[writable,signer]` payer of the rent of the new addresses
+    ///     4. `[]` system-program
+    AddPolicyAddresses(
+        PolicyKind,
+        Vec<Pubkey>
+    ),
+    /// Unlists the addresses from the policy of the kind, addresses not listed are ignored
+    /// 
+    /// The policy account shrinks accordingly, its rent is reclaimed when it is closed
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` admin
+    ///     1. `[]` config PDA
+    ///     2. `[writable]` policy PDA
+    RemovePolicyAddresses(
+        PolicyKind,
+        Vec<Pubkey>
+    ),
+    /// Closes the policy PDA of the kind, which is no longer enforced
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` admin
+    ///     1. `[]` config PDA
+    ///     2. `[writable]` policy PDA
+    ///     3. `[writable]` account receiving the rent of the policy account
+    ClosePolicy(PolicyKind),
+    /// Creates the split plan PDA of the authority with the id, the asset, recipients
+    /// and allocation
+    /// 
+    /// The plan starts at version 0, or right after the version of the plan closed at the
+    /// same address
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[writable,signer]` payer of the plan account
+    ///     1. `[signer]` authority
+    ///     2. `[writable]` split plan PDA
+    ///     3. `[]` system-program
+    CreatePlan(
+        u64,
+        PlanAsset,
+        Vec<Pubkey>,
+        PlanAllocation
+    ),
+    /// Replaces the recipients and allocation of the split plan and bumps its version
+    /// 
+    /// Accounts expected by this instruction and `LockPlan`:
+    /// 
+    ///     0. `[signer]` authority
+    ///     1. `[writable]` split plan PDA
+    UpdatePlan(
+        Vec<Pubkey>,
+        PlanAllocation
+    ),
+    /// Closes the split plan, unless it is locked
+    /// 
+    /// The account shrinks to a `PlanTombstone` keeping the version of the plan, the rest
+    /// of its rent is sent to the receiver
+    /// 
+    /// Accounts expected by this instruction:
+    /// 
+    ///     0. `[signer]` authority
+    ///     1. `[writable]` split plan PDA
+    ///     2. `[writable]` account receiving the rent of the plan account
+    ClosePlan,
+    /// Locks the split plan for good and bumps its version, it can no longer be updated nor closed
+    LockPlan,
+    /// Runs the split plan at the expected version, the total is allocated by the shares of
+    /// the plan and ignored by plans of fixed amounts
+    /// 
+    /// The options are those of the split the plan runs, `Lamports` for lamport plans and
+    /// `Token` for token plans, and apply like in the direct splits
+    /// 
+    /// Accounts expected by this instruction, those of `SplitLamports` for lamport plans and
+    /// of `SplitSplTokensFromSingleMint` for token plans with the plan inserted second:
+    /// 
+    ///     0. `[writable,signer]` source account of lamport plans, `[signer]` operator of token plans
+    ///     1. `[]` split plan PDA
+    ///     2. .. the remaining accounts of the split, the destinations being the recipients of the plan in order
+    ExecutePlan(
+        u64,
+        u64,
+        PlanSplitOptions
     )
 }
 
 impl SplitterInstruction {
     pub fn unpack(ix_data: &[u8]) -> Result<Self, ProgramError> {
-        if ix_data.len() <= 8 {
+        if ix_data.len() < 8 {
             return Err(
-                ProgramError::InvalidInstructionData
+                SplitterError::InvalidInstruction.into()
             );
         };
 
         let (
             ix_splitlamports,
             ix_splitspltokensfromsinglemint,
-            ix_splitspltokensfrommultiplemints 
+            ix_splitspltokensfrommultiplemints,
+            ix_splitspltokensbyroutes,
+            ix_splitlamportsbyshares,
+            ix_splitspltokensfromsinglemintbyshares,
+            ix_splitspltokensfromsingleminttowallets,
+            ix_splitspltokensfromsinglemintbysharestowallets,
+            ix_splitlamportstowrappedsol,
+            ix_splitwrappedsoltolamports,
+            ix_splitmixedassets,
+            ix_quotesplit,
+            ix_initializeconfig,
+            ix_proposeadmin,
+            ix_acceptadmin,
+            ix_queueconfigchange,
+            ix_executeconfigchange,
+            ix_cancelconfigchange,
+            ix_setpaused,
+            ix_initializepolicy,
+            ix_addpolicyaddresses,
+            ix_removepolicyaddresses,
+            ix_closepolicy,
+            ix_createplan,
+            ix_updateplan,
+            ix_closeplan,
+            ix_lockplan,
+            ix_executeplan
         ) = (
             Self::get_discriminator("instruction:splitlamports"),
             Self::get_discriminator("instruction:splitspltokensfromsinglemint"),
-            Self::get_discriminator("instruction:splitspltokensfrommultiplemints")
+            Self::get_discriminator("instruction:splitspltokensfrommultiplemints"),
+            Self::get_discriminator("instruction:splitspltokensbyroutes"),
+            Self::get_discriminator("instruction:splitlamportsbyshares"),
+            Self::get_discriminator("instruction:splitspltokensfromsinglemintbyshares"),
+            Self::get_discriminator("instruction:splitspltokensfromsingleminttowallets"),
+            Self::get_discriminator("instruction:splitspltokensfromsinglemintbysharestowallets"),
+            Self::get_discriminator("instruction:splitlamportstowrappedsol"),
+            Self::get_discriminator("instruction:splitwrappedsoltolamports"),
+            Self::get_discriminator("instruction:splitmixedassets"),
+            Self::get_discriminator("instruction:quotesplit"),
+            Self::get_discriminator("instruction:initializeconfig"),
+            Self::get_discriminator("instruction:proposeadmin"),
+            Self::get_discriminator("instruction:acceptadmin"),
+            Self::get_discriminator("instruction:queueconfigchange"),
+            Self::get_discriminator("instruction:executeconfigchange"),
+            Self::get_discriminator("instruction:cancelconfigchange"),
+            Self::get_discriminator("instruction:setpaused"),
+            Self::get_discriminator("instruction:initializepolicy"),
+            Self::get_discriminator("instruction:addpolicyaddresses"),
+            Self::get_discriminator("instruction:removepolicyaddresses"),
+            Self::get_discriminator("instruction:closepolicy"),
+            Self::get_discriminator("instruction:createplan"),
+            Self::get_discriminator("instruction:updateplan"),
+            Self::get_discriminator("instruction:closeplan"),
+            Self::get_discriminator("instruction:lockplan"),
+            Self::get_discriminator("instruction:executeplan")
         );
 
-        let ix_identifier: [u8; 8] = ix_data[..8].try_into().unwrap();
+        let (ix_identifier, data) = ix_data.split_at(8);
         if ix_identifier == ix_splitlamports {
-            let data = &ix_data[8..];
-            let ix = SplitLamports::try_from_slice(&data).unwrap();
+            let ix = Self::deserialize::<SplitLamports>(data)?;
 
             return Ok(
-                Self::SplitLamports(ix.amounts)
+                Self::SplitLamports(
+                    ix.amounts,
+                    ix.options
+                )
             );
         } else if ix_identifier == ix_splitspltokensfromsinglemint {
-            let data = &ix_data[8..];
-            let ix = SplitSplTokensFromSingleMint::try_from_slice(&data).unwrap();
+            let ix = Self::deserialize::<SplitSplTokensFromSingleMint>(data)?;
 
             return Ok(
-                Self::SplitSplTokensFromSingleMint(ix.amounts)
+                Self::SplitSplTokensFromSingleMint(
+                    ix.amounts,
+                    ix.decimals,
+                    ix.options
+                )
             );
         } else if ix_identifier == ix_splitspltokensfrommultiplemints {
-            let data = &ix_data[8..];
-            let ix = SplitSplTokensFromMultipleMints::try_from_slice(&data).unwrap();
+            let ix = Self::deserialize::<SplitSplTokensFromMultipleMints>(data)?;
 
             return Ok(
                 Self::SplitSplTokensFromMultipleMints(
                     ix.amounts,
-                    ix.m
+                    ix.m,
+                    ix.decimals,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitspltokensbyroutes {
+            let ix = Self::deserialize::<SplitSplTokensByRoutes>(data)?;
+
+            return Ok(
+                Self::SplitSplTokensByRoutes(
+                    ix.routes,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitlamportsbyshares {
+            let ix = Self::deserialize::<SplitLamportsByShares>(data)?;
+
+            return Ok(
+                Self::SplitLamportsByShares(
+                    ix.total,
+                    ix.shares,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitspltokensfromsinglemintbyshares {
+            let ix = Self::deserialize::<SplitSplTokensFromSingleMintByShares>(data)?;
+
+            return Ok(
+                Self::SplitSplTokensFromSingleMintByShares(
+                    ix.total,
+                    ix.shares,
+                    ix.sweep,
+                    ix.decimals,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitspltokensfromsingleminttowallets {
+            let ix = Self::deserialize::<SplitSplTokensFromSingleMintToWallets>(data)?;
+
+            return Ok(
+                Self::SplitSplTokensFromSingleMintToWallets(
+                    ix.amounts,
+                    ix.decimals,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitspltokensfromsinglemintbysharestowallets {
+            let ix = Self::deserialize::<SplitSplTokensFromSingleMintBySharesToWallets>(data)?;
+
+            return Ok(
+                Self::SplitSplTokensFromSingleMintBySharesToWallets(
+                    ix.total,
+                    ix.shares,
+                    ix.sweep,
+                    ix.decimals,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitlamportstowrappedsol {
+            let ix = Self::deserialize::<SplitLamportsToWrappedSol>(data)?;
+
+            return Ok(
+                Self::SplitLamportsToWrappedSol(
+                    ix.amounts,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitwrappedsoltolamports {
+            let ix = Self::deserialize::<SplitWrappedSolToLamports>(data)?;
+
+            return Ok(
+                Self::SplitWrappedSolToLamports(
+                    ix.amounts,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_splitmixedassets {
+            let ix = Self::deserialize::<SplitMixedAssets>(data)?;
+
+            return Ok(
+                Self::SplitMixedAssets(
+                    ix.legs,
+                    ix.options
+                )
+            );
+        } else if ix_identifier == ix_quotesplit {
+            let ix = Self::deserialize::<QuoteSplit>(data)?;
+
+            return Ok(
+                Self::QuoteSplit(
+                    ix.total,
+                    ix.shares,
+                    ix.sweep
+                )
+            );
+        } else if ix_identifier == ix_initializeconfig {
+            let ix = Self::deserialize::<InitializeConfig>(data)?;
+
+            return Ok(
+                Self::InitializeConfig(
+                    ix.admin,
+                    ix.fee_basis_points,
+                    ix.treasury,
+                    ix.timelock_delay
+                )
+            );
+        } else if ix_identifier == ix_proposeadmin {
+            let ix = Self::deserialize::<ProposeAdmin>(data)?;
+
+            return Ok(
+                Self::ProposeAdmin(ix.admin)
+            );
+        } else if ix_identifier == ix_acceptadmin {
+            Self::deserialize::<AcceptAdmin>(data)?;
+
+            return Ok(
+                Self::AcceptAdmin
+            );
+        } else if ix_identifier == ix_queueconfigchange {
+            let ix = Self::deserialize::<QueueConfigChange>(data)?;
+
+            return Ok(
+                Self::QueueConfigChange(ix.change)
+            );
+        } else if ix_identifier == ix_executeconfigchange {
+            let ix = Self::deserialize::<ExecuteConfigChange>(data)?;
+
+            return Ok(
+                Self::ExecuteConfigChange(ix.id)
+            );
+        } else if ix_identifier == ix_cancelconfigchange {
+            let ix = Self::deserialize::<CancelConfigChange>(data)?;
+
+            return Ok(
+                Self::CancelConfigChange(ix.id)
+            );
+        } else if ix_identifier == ix_setpaused {
+            let ix = Self::deserialize::<SetPaused>(data)?;
+
+            return Ok(
+                Self::SetPaused(ix.paused)
+            );
+        } else if ix_identifier == ix_initializepolicy {
+            let ix = Self::deserialize::<InitializePolicy>(data)?;
+
+            return Ok(
+                Self::InitializePolicy(ix.kind)
+            );
+        } else if ix_identifier == ix_addpolicyaddresses {
+            let ix = Self::deserialize::<AddPolicyAddresses>(data)?;
+
+            return Ok(
+                Self::AddPolicyAddresses(
+                    ix.kind,
+                    ix.addresses
+                )
+            );
+        } else if ix_identifier == ix_removepolicyaddresses {
+            let ix = Self::deserialize::<RemovePolicyAddresses>(data)?;
+
+            return Ok(
+                Self::RemovePolicyAddresses(
+                    ix.kind,
+                    ix.addresses
+                )
+            );
+        } else if ix_identifier == ix_closepolicy {
+            let ix = Self::deserialize::<ClosePolicy>(data)?;
+
+            return Ok(
+                Self::ClosePolicy(ix.kind)
+            );
+        } else if ix_identifier == ix_createplan {
+            let ix = Self::deserialize::<CreatePlan>(data)?;
+
+            return Ok(
+                Self::CreatePlan(
+                    ix.id,
+                    ix.asset,
+                    ix.recipients,
+                    ix.allocation
+                )
+            );
+        } else if ix_identifier == ix_updateplan {
+            let ix = Self::deserialize::<UpdatePlan>(data)?;
+
+            return Ok(
+                Self::UpdatePlan(
+                    ix.recipients,
+                    ix.allocation
+                )
+            );
+        } else if ix_identifier == ix_closeplan {
+            Self::deserialize::<ClosePlan>(data)?;
+
+            return Ok(
+                Self::ClosePlan
+            );
+        } else if ix_identifier == ix_lockplan {
+            Self::deserialize::<LockPlan>(data)?;
+
+            return Ok(
+                Self::LockPlan
+            );
+        } else if ix_identifier == ix_executeplan {
+            let ix = Self::deserialize::<ExecutePlan>(data)?;
+
+            return Ok(
+                Self::ExecutePlan(
+                    ix.total,
+                    ix.expected_version,
+                    ix.options
                 )
             );
         } else {
             return Err(
-                ProgramError::InvalidInstructionData
+                SplitterError::InvalidInstruction.into()
             );
         };
     }
 
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let (discriminator, data) = match self {
+            Self::SplitLamports(amounts, options) => (
+                Self::get_discriminator("instruction:splitlamports"),
+                Self::serialize(&SplitLamports {
+                    amounts: amounts.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensFromSingleMint(amounts, decimals, options) => (
+                Self::get_discriminator("instruction:splitspltokensfromsinglemint"),
+                Self::serialize(&SplitSplTokensFromSingleMint {
+                    amounts: amounts.clone(),
+                    decimals: *decimals,
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensFromMultipleMints(amounts, m, decimals, options) => (
+                Self::get_discriminator("instruction:splitspltokensfrommultiplemints"),
+                Self::serialize(&SplitSplTokensFromMultipleMints {
+                    amounts: amounts.clone(),
+                    m: *m,
+                    decimals: decimals.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensByRoutes(routes, options) => (
+                Self::get_discriminator("instruction:splitspltokensbyroutes"),
+                Self::serialize(&SplitSplTokensByRoutes {
+                    routes: routes.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitLamportsByShares(total, shares, options) => (
+                Self::get_discriminator("instruction:splitlamportsbyshares"),
+                Self::serialize(&SplitLamportsByShares {
+                    total: *total,
+                    shares: shares.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensFromSingleMintByShares(total, shares, sweep, decimals, options) => (
+                Self::get_discriminator("instruction:splitspltokensfromsinglemintbyshares"),
+                Self::serialize(&SplitSplTokensFromSingleMintByShares {
+                    total: *total,
+                    shares: shares.clone(),
+                    sweep: *sweep,
+                    decimals: *decimals,
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensFromSingleMintToWallets(amounts, decimals, options) => (
+                Self::get_discriminator("instruction:splitspltokensfromsingleminttowallets"),
+                Self::serialize(&SplitSplTokensFromSingleMintToWallets {
+                    amounts: amounts.clone(),
+                    decimals: *decimals,
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitSplTokensFromSingleMintBySharesToWallets(total, shares, sweep, decimals, options) => (
+                Self::get_discriminator("instruction:splitspltokensfromsinglemintbysharestowallets"),
+                Self::serialize(&SplitSplTokensFromSingleMintBySharesToWallets {
+                    total: *total,
+                    shares: shares.clone(),
+                    sweep: *sweep,
+                    decimals: *decimals,
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitLamportsToWrappedSol(amounts, options) => (
+                Self::get_discriminator("instruction:splitlamportstowrappedsol"),
+                Self::serialize(&SplitLamportsToWrappedSol {
+                    amounts: amounts.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitWrappedSolToLamports(amounts, options) => (
+                Self::get_discriminator("instruction:splitwrappedsoltolamports"),
+                Self::serialize(&SplitWrappedSolToLamports {
+                    amounts: amounts.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::SplitMixedAssets(legs, options) => (
+                Self::get_discriminator("instruction:splitmixedassets"),
+                Self::serialize(&SplitMixedAssets {
+                    legs: legs.clone(),
+                    options: options.clone()
+                })?
+            ),
+            Self::QuoteSplit(total, shares, sweep) => (
+                Self::get_discriminator("instruction:quotesplit"),
+                Self::serialize(&QuoteSplit {
+                    total: *total,
+                    shares: shares.clone(),
+                    sweep: *sweep
+                })?
+            ),
+            Self::InitializeConfig(admin, fee_basis_points, treasury, timelock_delay) => (
+                Self::get_discriminator("instruction:initializeconfig"),
+                Self::serialize(&InitializeConfig {
+                    admin: *admin,
+                    fee_basis_points: *fee_basis_points,
+                    treasury: *treasury,
+                    timelock_delay: *timelock_delay
+                })?
+            ),
+            Self::ProposeAdmin(admin) => (
+                Self::get_discriminator("instruction:proposeadmin"),
+                Self::serialize(&ProposeAdmin {
+                    admin: *admin
+                })?
+            ),
+            Self::AcceptAdmin => (
+                Self::get_discriminator("instruction:acceptadmin"),
+                Self::serialize(&AcceptAdmin {})?
+            ),
+            Self::QueueConfigChange(change) => (
+                Self::get_discriminator("instruction:queueconfigchange"),
+                Self::serialize(&QueueConfigChange {
+                    change: change.clone()
+                })?
+            ),
+            Self::ExecuteConfigChange(id) => (
+                Self::get_discriminator("instruction:executeconfigchange"),
+                Self::serialize(&ExecuteConfigChange {
+                    id: *id
+                })?
+            ),
+            Self::CancelConfigChange(id) => (
+                Self::get_discriminator("instruction:cancelconfigchange"),
+                Self::serialize(&CancelConfigChange {
+                    id: *id
+                })?
+            ),
+            Self::SetPaused(paused) => (
+                Self::get_discriminator("instruction:setpaused"),
+                Self::serialize(&SetPaused {
+                    paused: *paused
+                })?
+            ),
+            Self::InitializePolicy(kind) => (
+                Self::get_discriminator("instruction:initializepolicy"),
+                Self::serialize(&InitializePolicy {
+                    kind: *kind
+                })?
+            ),
+            Self::AddPolicyAddresses(kind, addresses) => (
+                Self::get_discriminator("instruction:addpolicyaddresses"),
+                Self::serialize(&AddPolicyAddresses {
+                    kind: *kind,
+                    addresses: addresses.clone()
+                })?
+            ),
+            Self::RemovePolicyAddresses(kind, addresses) => (
+                Self::get_discriminator("instruction:removepolicyaddresses"),
+                Self::serialize(&RemovePolicyAddresses {
+                    kind: *kind,
+                    addresses: addresses.clone()
+                })?
+            ),
+            Self::ClosePolicy(kind) => (
+                Self::get_discriminator("instruction:closepolicy"),
+                Self::serialize(&ClosePolicy {
+                    kind: *kind
+                })?
+            ),
+            Self::CreatePlan(id, asset, recipients, allocation) => (
+                Self::get_discriminator("instruction:createplan"),
+                Self::serialize(&CreatePlan {
+                    id: *id,
+                    asset: asset.clone(),
+                    recipients: recipients.clone(),
+                    allocation: allocation.clone()
+                })?
+            ),
+            Self::UpdatePlan(recipients, allocation) => (
+                Self::get_discriminator("instruction:updateplan"),
+                Self::serialize(&UpdatePlan {
+                    recipients: recipients.clone(),
+                    allocation: allocation.clone()
+                })?
+            ),
+            Self::ClosePlan => (
+                Self::get_discriminator("instruction:closeplan"),
+                Self::serialize(&ClosePlan {})?
+            ),
+            Self::LockPlan => (
+                Self::get_discriminator("instruction:lockplan"),
+                Self::serialize(&LockPlan {})?
+            ),
+            Self::ExecutePlan(total, expected_version, options) => (
+                Self::get_discriminator("instruction:executeplan"),
+                Self::serialize(&ExecutePlan {
+                    total: *total,
+                    expected_version: *expected_version,
+                    options: options.clone()
+                })?
+            )
+        };
+
+        let mut buf = Vec::with_capacity(8 + data.len());
+        buf.extend_from_slice(&discriminator);
+        buf.extend_from_slice(&data);
+
+        Ok(buf)
+    }
+
+    /// Kind reported in the summary of the instruction, `None` when it is not a split.
+    pub fn kind(&self) -> Option<SplitKind> {
+        match self {
+            Self::SplitLamports(..) => Some(SplitKind::SplitLamports),
+            Self::SplitSplTokensFromSingleMint(..) => Some(SplitKind::SplitSplTokensFromSingleMint),
+            Self::SplitSplTokensFromMultipleMints(..) => Some(SplitKind::SplitSplTokensFromMultipleMints),
+            Self::SplitSplTokensByRoutes(..) => Some(SplitKind::SplitSplTokensByRoutes),
+            Self::SplitLamportsByShares(..) => Some(SplitKind::SplitLamportsByShares),
+            Self::SplitSplTokensFromSingleMintByShares(..) => Some(SplitKind::SplitSplTokensFromSingleMintByShares),
+            Self::SplitSplTokensFromSingleMintToWallets(..) => Some(SplitKind::SplitSplTokensFromSingleMintToWallets),
+            Self::SplitSplTokensFromSingleMintBySharesToWallets(..) => Some(SplitKind::SplitSplTokensFromSingleMintBySharesToWallets),
+            Self::SplitLamportsToWrappedSol(..) => Some(SplitKind::SplitLamportsToWrappedSol),
+            Self::SplitWrappedSolToLamports(..) => Some(SplitKind::SplitWrappedSolToLamports),
+            Self::SplitMixedAssets(..) => Some(SplitKind::SplitMixedAssets),
+            Self::ExecutePlan(..) => Some(SplitKind::ExecutePlan),
+            Self::QuoteSplit(..)
+            | Self::InitializeConfig(..)
+            | Self::ProposeAdmin(..)
+            | Self::AcceptAdmin
+            | Self::QueueConfigChange(..)
+            | Self::ExecuteConfigChange(..)
+            | Self::CancelConfigChange(..)
+            | Self::SetPaused(..)
+            | Self::InitializePolicy(..)
+            | Self::AddPolicyAddresses(..)
+            | Self::RemovePolicyAddresses(..)
+            | Self::ClosePolicy(..)
+            | Self::CreatePlan(..)
+            | Self::UpdatePlan(..)
+            | Self::ClosePlan
+            | Self::LockPlan => None
+        }
+    }
+
+    /// Balance guards attached to the instruction.
+    pub fn guards(&self) -> &[BalanceGuard] {
+        match self {
+            Self::SplitLamports(_, options)
+            | Self::SplitLamportsByShares(_, _, options)
+            | Self::SplitLamportsToWrappedSol(_, options)
+            | Self::SplitWrappedSolToLamports(_, options) => &options.guards,
+            Self::SplitSplTokensFromSingleMint(_, _, options)
+            | Self::SplitSplTokensFromMultipleMints(_, _, _, options)
+            | Self::SplitSplTokensByRoutes(_, options)
+            | Self::SplitSplTokensFromSingleMintByShares(_, _, _, _, options)
+            | Self::SplitSplTokensFromSingleMintToWallets(_, _, options)
+            | Self::SplitSplTokensFromSingleMintBySharesToWallets(_, _, _, _, options)
+            | Self::SplitMixedAssets(_, options) => &options.guards,
+            Self::ExecutePlan(_, _, options) => options.guards(),
+            Self::QuoteSplit(..)
+            | Self::InitializeConfig(..)
+            | Self::ProposeAdmin(..)
+            | Self::AcceptAdmin
+            | Self::QueueConfigChange(..)
+            | Self::ExecuteConfigChange(..)
+            | Self::CancelConfigChange(..)
+            | Self::SetPaused(..)
+            | Self::InitializePolicy(..)
+            | Self::AddPolicyAddresses(..)
+            | Self::RemovePolicyAddresses(..)
+            | Self::ClosePolicy(..)
+            | Self::CreatePlan(..)
+            | Self::UpdatePlan(..)
+            | Self::ClosePlan
+            | Self::LockPlan => &[]
+        }
+    }
+
     pub fn get_discriminator(dis: &str) -> [u8; 8] {
         let sha256 = hash(
             dis.as_bytes().as_ref()
         );
-    
-        let dis: [u8; 8] = sha256.as_ref()[..8].try_into().unwrap();
-        dis
+
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(&sha256.as_ref()[..8]);
+        discriminator
     }
+
+    fn deserialize<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+        T::try_from_slice(data).map_err(|_| SplitterError::InvalidInstruction.into())
+    }
+
+    fn serialize<T: BorshSerialize>(data: &T) -> Result<Vec<u8>, ProgramError> {
+        data.try_to_vec().map_err(|_| SplitterError::InvalidInstruction.into())
+    }
+}
+
+/// Creates a `SplitLamports` instruction.
+pub fn split_lamports(
+    program_id: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    amounts: Vec<u64>,
+    options: LamportSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, amounts.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: lamports_split_account_metas(program_id, source, destinations, &options),
+            data: SplitterInstruction::SplitLamports(
+                amounts,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitLamportsByShares` instruction.
+pub fn split_lamports_by_shares(
+    program_id: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    total: u64,
+    shares: Vec<u64>,
+    options: LamportSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, shares.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: lamports_split_account_metas(program_id, source, destinations, &options),
+            data: SplitterInstruction::SplitLamportsByShares(
+                total,
+                shares,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitLamportsToWrappedSol` instruction.
+pub fn split_lamports_to_wrapped_sol(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    amounts: Vec<u64>,
+    options: LamportSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, amounts.len())?;
+
+    let mut accounts = Vec::with_capacity(destinations.len() + 3);
+    accounts.push(AccountMeta::new(*source, true));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.extend(destinations.iter().map(|destination| AccountMeta::new(*destination, false)));
+    accounts.extend(lamport_option_account_metas(program_id, &options));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::SplitLamportsToWrappedSol(
+                amounts,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitWrappedSolToLamports` instruction, the native mint is the one of
+/// the token program.
+pub fn split_wrapped_sol_to_lamports(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    source: &Pubkey,
+    scratch: &Pubkey,
+    destinations: &[Pubkey],
+    amounts: Vec<u64>,
+    options: LamportSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, amounts.len())?;
+
+    let native_mint = if *token_program_id == spl_token_2022::ID {
+        spl_token_2022::native_mint::ID
+    } else {
+        spl_token::native_mint::ID
+    };
+
+    let mut accounts = Vec::with_capacity(destinations.len() + 6);
+    accounts.push(AccountMeta::new(*operator, true));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(native_mint, false));
+    accounts.push(AccountMeta::new(*source, false));
+    accounts.push(AccountMeta::new(*scratch, false));
+    accounts.extend(destinations.iter().map(|destination| AccountMeta::new(*destination, false)));
+    accounts.extend(lamport_option_account_metas(program_id, &options));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::SplitWrappedSolToLamports(
+                amounts,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensFromSingleMint` instruction.
+///
+/// Mints with a transfer hook need their extra accounts appended, see
+/// `offchain::split_spl_tokens_from_single_mint_with_extra_metas`.
+pub fn split_spl_tokens_from_single_mint(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, amounts.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: single_mint_split_account_metas(
+                program_id,
+                token_program_id,
+                operator,
+                signer_pubkeys,
+                mint,
+                source,
+                destinations,
+                &options
+            ),
+            data: SplitterInstruction::SplitSplTokensFromSingleMint(
+                amounts,
+                decimals,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensFromSingleMintByShares` instruction.
+///
+/// Mints with a transfer hook need their extra accounts appended, see
+/// `offchain::split_spl_tokens_from_single_mint_by_shares_with_extra_metas`.
+pub fn split_spl_tokens_from_single_mint_by_shares(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    total: u64,
+    shares: Vec<u64>,
+    sweep: bool,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(destinations, shares.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: single_mint_split_account_metas(
+                program_id,
+                token_program_id,
+                operator,
+                signer_pubkeys,
+                mint,
+                source,
+                destinations,
+                &options
+            ),
+            data: SplitterInstruction::SplitSplTokensFromSingleMintByShares(
+                total,
+                shares,
+                sweep,
+                decimals,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensFromSingleMintToWallets` instruction, the destinations are
+/// the associated token accounts of `wallets`.
+///
+/// Mints with a transfer hook need their extra accounts appended, see
+/// `offchain::split_spl_tokens_from_single_mint_to_wallets_with_extra_metas`.
+pub fn split_spl_tokens_from_single_mint_to_wallets(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    rent_payer: &Pubkey,
+    wallets: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(wallets, amounts.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: wallets_split_account_metas(
+                program_id,
+                token_program_id,
+                operator,
+                signer_pubkeys,
+                mint,
+                source,
+                rent_payer,
+                wallets,
+                &options
+            ),
+            data: SplitterInstruction::SplitSplTokensFromSingleMintToWallets(
+                amounts,
+                decimals,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensFromSingleMintBySharesToWallets` instruction, the
+/// destinations are the associated token accounts of `wallets`.
+///
+/// Mints with a transfer hook need their extra accounts appended, see
+/// `offchain::split_spl_tokens_from_single_mint_by_shares_to_wallets_with_extra_metas`.
+pub fn split_spl_tokens_from_single_mint_by_shares_to_wallets(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    rent_payer: &Pubkey,
+    wallets: &[Pubkey],
+    total: u64,
+    shares: Vec<u64>,
+    sweep: bool,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(wallets, shares.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: wallets_split_account_metas(
+                program_id,
+                token_program_id,
+                operator,
+                signer_pubkeys,
+                mint,
+                source,
+                rent_payer,
+                wallets,
+                &options
+            ),
+            data: SplitterInstruction::SplitSplTokensFromSingleMintBySharesToWallets(
+                total,
+                shares,
+                sweep,
+                decimals,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensFromMultipleMints` instruction, `m` is the number of sources.
+///
+/// Mints with a transfer hook need their extra accounts appended, see
+/// `offchain::split_spl_tokens_from_multiple_mints_with_extra_metas`.
+pub fn split_spl_tokens_from_multiple_mints(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    sources: &[Pubkey],
+    destinations: &[Pubkey],
+    mints: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: Vec<u8>,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    let m = sources.len();
+    if destinations.len() != m
+        || mints.len() != m
+        || amounts.len() != m
+        || decimals.len() != m
+    {
+        return Err(
+            SplitterError::InvalidMParameter.into()
+        );
+    };
+    let m = u16::try_from(m).map_err(|_| SplitterError::InvalidMParameter)?;
+
+    let mut accounts = Vec::with_capacity(3 * sources.len() + 2);
+    accounts.push(AccountMeta::new_readonly(*operator, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.extend(sources.iter().map(|source| AccountMeta::new(*source, false)));
+    accounts.extend(destinations.iter().map(|destination| AccountMeta::new(*destination, false)));
+    accounts.extend(mints.iter().map(|mint| AccountMeta::new_readonly(*mint, false)));
+    accounts.extend(signer_account_metas(signer_pubkeys));
+    accounts.extend(option_account_metas(program_id, &options));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::SplitSplTokensFromMultipleMints(
+                amounts,
+                m,
+                decimals,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitSplTokensByRoutes` instruction.
+///
+/// `route_accounts` are the remaining accounts the routes index into, the ones used as
+/// a source or a destination are marked writable. Mints with a transfer hook need their
+/// extra accounts appended, see `offchain::split_spl_tokens_by_routes_with_extra_metas`.
+pub fn split_spl_tokens_by_routes(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    route_accounts: &[Pubkey],
+    routes: Vec<TransferRoute>,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = Vec::with_capacity(route_accounts.len() + 2);
+    accounts.push(AccountMeta::new_readonly(*operator, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.extend(
+        route_accounts
+            .iter()
+            .enumerate()
+            .map(|(index, pubkey)| {
+                let is_writable = routes.iter().any(|route| {
+                    route.source_index as usize == index
+                        || route.destination_index as usize == index
+                });
+
+                AccountMeta {
+                    pubkey: *pubkey,
+                    is_signer: false,
+                    is_writable
+                }
+            })
+    );
+    accounts.extend(signer_account_metas(signer_pubkeys));
+    accounts.extend(option_account_metas(program_id, &options));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::SplitSplTokensByRoutes(
+                routes,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `SplitMixedAssets` instruction.
+///
+/// `leg_accounts` are the accounts the legs index into, the ones paid or used as a
+/// token source are marked writable. Mints with a transfer hook need their extra
+/// accounts appended, see `offchain::split_mixed_assets_with_extra_metas`.
+pub fn split_mixed_assets(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    leg_accounts: &[Pubkey],
+    legs: Vec<SplitLeg>,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    let pays_lamports = legs.iter().any(|leg| matches!(leg, SplitLeg::Lamports { .. }));
+
+    let mut accounts = Vec::with_capacity(leg_accounts.len() + 3);
+    accounts.push(AccountMeta {
+        pubkey: *operator,
+        is_signer: signer_pubkeys.is_empty(),
+        is_writable: pays_lamports
+    });
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    accounts.extend(
+        leg_accounts
+            .iter()
+            .enumerate()
+            .map(|(index, pubkey)| {
+                let is_writable = legs.iter().any(|leg| match leg {
+                    SplitLeg::Lamports { destination_index, .. } => *destination_index as usize == index,
+                    SplitLeg::Token(route) => {
+                        route.source_index as usize == index
+                            || route.destination_index as usize == index
+                    }
+                });
+
+                AccountMeta {
+                    pubkey: *pubkey,
+                    is_signer: false,
+                    is_writable
+                }
+            })
+    );
+    accounts.extend(signer_account_metas(signer_pubkeys));
+    accounts.extend(option_account_metas(program_id, &options));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::SplitMixedAssets(
+                legs,
+                options
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `QuoteSplit` instruction that allocates `total` by shares.
+pub fn quote_split(
+    program_id: &Pubkey,
+    total: u64,
+    shares: Vec<u64>
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(get_config_address(program_id).0, false)
+            ],
+            data: SplitterInstruction::QuoteSplit(
+                total,
+                shares,
+                false
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `QuoteSplit` instruction that allocates the whole balance of the source
+/// token account by shares.
+pub fn quote_split_sweep(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    source: &Pubkey,
+    shares: Vec<u64>
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*token_program_id, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(*source, false),
+                AccountMeta::new_readonly(get_config_address(program_id).0, false)
+            ],
+            data: SplitterInstruction::QuoteSplit(
+                0u64,
+                shares,
+                true
+            ).pack()?
+        }
+    )
+}
+
+/// Creates an `InitializeConfig` instruction.
+pub fn initialize_config(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    upgrade_authority: &Pubkey,
+    admin: &Pubkey,
+    fee_basis_points: u16,
+    treasury: &Pubkey,
+    timelock_delay: u32
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(*upgrade_authority, true),
+                AccountMeta::new_readonly(get_program_data_address(program_id), false),
+                AccountMeta::new(get_config_address(program_id).0, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: SplitterInstruction::InitializeConfig(
+                *admin,
+                fee_basis_points,
+                *treasury,
+                timelock_delay
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `ProposeAdmin` instruction.
+pub fn propose_admin(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    new_admin: Option<Pubkey>
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, admin),
+            data: SplitterInstruction::ProposeAdmin(new_admin).pack()?
+        }
+    )
+}
+
+/// Creates an `AcceptAdmin` instruction.
+pub fn accept_admin(
+    program_id: &Pubkey,
+    new_admin: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, new_admin),
+            data: SplitterInstruction::AcceptAdmin.pack()?
+        }
+    )
+}
+
+/// Creates a `QueueConfigChange` instruction.
+pub fn queue_config_change(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    change: ConfigChange
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, admin),
+            data: SplitterInstruction::QueueConfigChange(change).pack()?
+        }
+    )
+}
+
+/// Creates an `ExecuteConfigChange` instruction.
+pub fn execute_config_change(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    id: u64
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, admin),
+            data: SplitterInstruction::ExecuteConfigChange(id).pack()?
+        }
+    )
+}
+
+/// Creates a `CancelConfigChange` instruction.
+pub fn cancel_config_change(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    id: u64
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, admin),
+            data: SplitterInstruction::CancelConfigChange(id).pack()?
+        }
+    )
+}
+
+/// Creates a `SetPaused` instruction.
+pub fn set_paused(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    paused: bool
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: config_update_account_metas(program_id, admin),
+            data: SplitterInstruction::SetPaused(paused).pack()?
+        }
+    )
+}
+
+/// Creates an `InitializePolicy` instruction.
+pub fn initialize_policy(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    admin: &Pubkey,
+    kind: PolicyKind
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new_readonly(get_config_address(program_id).0, false),
+                AccountMeta::new(get_policy_address(program_id, kind).0, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: SplitterInstruction::InitializePolicy(kind).pack()?
+        }
+    )
+}
+
+/// Creates an `AddPolicyAddresses` instruction.
+pub fn add_policy_addresses(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    admin: &Pubkey,
+    kind: PolicyKind,
+    addresses: Vec<Pubkey>
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = policy_update_account_metas(program_id, admin, kind);
+    accounts.push(AccountMeta::new(*payer, true));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::AddPolicyAddresses(kind, addresses).pack()?
+        }
+    )
+}
+
+/// Creates a `RemovePolicyAddresses` instruction.
+pub fn remove_policy_addresses(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    kind: PolicyKind,
+    addresses: Vec<Pubkey>
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: policy_update_account_metas(program_id, admin, kind),
+            data: SplitterInstruction::RemovePolicyAddresses(kind, addresses).pack()?
+        }
+    )
+}
+
+/// Creates a `ClosePolicy` instruction.
+pub fn close_policy(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    kind: PolicyKind,
+    receiver: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = policy_update_account_metas(program_id, admin, kind);
+    accounts.push(AccountMeta::new(*receiver, false));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::ClosePolicy(kind).pack()?
+        }
+    )
+}
+
+/// Creates a `CreatePlan` instruction.
+pub fn create_plan(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    id: u64,
+    asset: PlanAsset,
+    recipients: Vec<Pubkey>,
+    allocation: PlanAllocation
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(&recipients, allocation.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(get_plan_address(program_id, authority, id).0, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: SplitterInstruction::CreatePlan(
+                id,
+                asset,
+                recipients,
+                allocation
+            ).pack()?
+        }
+    )
+}
+
+/// Creates an `UpdatePlan` instruction.
+pub fn update_plan(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    id: u64,
+    recipients: Vec<Pubkey>,
+    allocation: PlanAllocation
+) -> Result<Instruction, ProgramError> {
+    check_destinations_len(&recipients, allocation.len())?;
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(get_plan_address(program_id, authority, id).0, false)
+            ],
+            data: SplitterInstruction::UpdatePlan(
+                recipients,
+                allocation
+            ).pack()?
+        }
+    )
+}
+
+/// Creates a `ClosePlan` instruction.
+pub fn close_plan(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    id: u64,
+    receiver: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(get_plan_address(program_id, authority, id).0, false),
+                AccountMeta::new(*receiver, false)
+            ],
+            data: SplitterInstruction::ClosePlan.pack()?
+        }
+    )
+}
+
+/// Creates a `LockPlan` instruction.
+pub fn lock_plan(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    id: u64
+) -> Result<Instruction, ProgramError> {
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(get_plan_address(program_id, authority, id).0, false)
+            ],
+            data: SplitterInstruction::LockPlan.pack()?
+        }
+    )
+}
+
+/// Creates an `ExecutePlan` instruction for a plan of lamports, expecting the version of `plan`.
+pub fn execute_lamports_plan(
+    program_id: &Pubkey,
+    source: &Pubkey,
+    plan: &SplitPlan,
+    total: u64,
+    options: LamportSplitOptions
+) -> Result<Instruction, ProgramError> {
+    if plan.asset != PlanAsset::Lamports {
+        return Err(
+            SplitterError::InvalidPlan.into()
+        );
+    };
+
+    let mut accounts = lamports_split_account_metas(
+        program_id,
+        source,
+        &plan.recipients,
+        &options
+    );
+    accounts.insert(1, plan_account_meta(program_id, plan));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::ExecutePlan(
+                total,
+                plan.version,
+                PlanSplitOptions::Lamports(options)
+            ).pack()?
+        }
+    )
+}
+
+/// Creates an `ExecutePlan` instruction for a plan of tokens, expecting the version of `plan`.
+pub fn execute_token_plan(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    source: &Pubkey,
+    plan: &SplitPlan,
+    total: u64,
+    options: TokenSplitOptions
+) -> Result<Instruction, ProgramError> {
+    let mint = match plan.asset {
+        PlanAsset::Token { mint, .. } => mint,
+        PlanAsset::Lamports => {
+            return Err(
+                SplitterError::InvalidPlan.into()
+            );
+        }
+    };
+
+    let mut accounts = single_mint_split_account_metas(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        &mint,
+        source,
+        &plan.recipients,
+        &options
+    );
+    accounts.insert(1, plan_account_meta(program_id, plan));
+
+    Ok(
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: SplitterInstruction::ExecutePlan(
+                total,
+                plan.version,
+                PlanSplitOptions::Token(options)
+            ).pack()?
+        }
+    )
+}
+
+/// Returns the treasury accounts a split must be given when the config charges a fee,
+/// the treasury itself for lamport transfers and its associated token account of each
+/// of the `mints` for token transfers.
+pub fn treasury_account_metas(
+    treasury: &Pubkey,
+    token_program_id: &Pubkey,
+    mints: &[Pubkey]
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(mints.len() + 1);
+    accounts.push(AccountMeta::new(*treasury, false));
+    accounts.extend(
+        mints
+            .iter()
+            .map(|mint| {
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(treasury, mint, token_program_id),
+                    false
+                )
+            })
+    );
+    accounts
+}
+
+fn check_destinations_len(destinations: &[Pubkey], amounts_len: usize) -> Result<(), ProgramError> {
+    if destinations.len() != amounts_len {
+        return Err(
+            SplitterError::AccountsAmountsMismatch.into()
+        );
+    };
+
+    Ok(())
+}
+
+fn signer_account_metas<'a>(signer_pubkeys: &'a [&Pubkey]) -> impl Iterator<Item = AccountMeta> + 'a {
+    signer_pubkeys
+        .iter()
+        .map(|signer_pubkey| AccountMeta::new_readonly(**signer_pubkey, true))
+}
+
+fn option_account_metas(program_id: &Pubkey, options: &TokenSplitOptions) -> impl Iterator<Item = AccountMeta> {
+    options
+        .close_sources_to
+        .map(|close_sources_to| AccountMeta::new(close_sources_to, false))
+        .into_iter()
+        .chain(memo_account_metas(&options.memo))
+        .chain(config_account_metas(program_id))
+}
+
+fn lamport_option_account_metas(program_id: &Pubkey, options: &LamportSplitOptions) -> impl Iterator<Item = AccountMeta> {
+    memo_account_metas(&options.memo).chain(config_account_metas(program_id))
+}
+
+/// Returns the config and policy PDAs every split expects after its other accounts.
+pub fn config_account_metas(program_id: &Pubkey) -> impl Iterator<Item = AccountMeta> {
+    [
+        get_config_address(program_id).0,
+        get_policy_address(program_id, PolicyKind::MintAllowlist).0,
+        get_policy_address(program_id, PolicyKind::RecipientDenylist).0
+    ]
+        .into_iter()
+        .map(|address| AccountMeta::new_readonly(address, false))
+}
+
+fn memo_account_metas(memo: &Option<SplitMemo>) -> impl Iterator<Item = AccountMeta> {
+    memo
+        .as_ref()
+        .map(|_| AccountMeta::new_readonly(spl_memo::ID, false))
+        .into_iter()
+}
+
+fn config_update_account_metas(program_id: &Pubkey, admin: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(get_config_address(program_id).0, false)
+    ]
+}
+
+fn plan_account_meta(program_id: &Pubkey, plan: &SplitPlan) -> AccountMeta {
+    AccountMeta::new_readonly(get_plan_address(program_id, &plan.authority, plan.id).0, false)
+}
+
+fn policy_update_account_metas(program_id: &Pubkey, admin: &Pubkey, kind: PolicyKind) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new_readonly(get_config_address(program_id).0, false),
+        AccountMeta::new(get_policy_address(program_id, kind).0, false)
+    ]
+}
+
+fn lamports_split_account_metas(
+    program_id: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    options: &LamportSplitOptions
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(destinations.len() + 4);
+    accounts.push(AccountMeta::new(*source, true));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    accounts.extend(destinations.iter().map(|destination| AccountMeta::new(*destination, false)));
+    accounts.extend(lamport_option_account_metas(program_id, options));
+    accounts
+}
+
+fn single_mint_split_account_metas(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    options: &TokenSplitOptions
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(destinations.len() + 4);
+    accounts.push(AccountMeta::new_readonly(*operator, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(*mint, false));
+    accounts.push(AccountMeta::new(*source, false));
+    accounts.extend(destinations.iter().map(|destination| AccountMeta::new(*destination, false)));
+    accounts.extend(signer_account_metas(signer_pubkeys));
+    accounts.extend(option_account_metas(program_id, options));
+    accounts
+}
+
+fn wallets_split_account_metas(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    rent_payer: &Pubkey,
+    wallets: &[Pubkey],
+    options: &TokenSplitOptions
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(2 * wallets.len() + 7);
+    accounts.push(AccountMeta::new_readonly(*operator, signer_pubkeys.is_empty()));
+    accounts.push(AccountMeta::new_readonly(*token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(*mint, false));
+    accounts.push(AccountMeta::new(*source, false));
+    accounts.push(AccountMeta::new(*rent_payer, true));
+    accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    accounts.push(AccountMeta::new_readonly(spl_associated_token_account::ID, false));
+    accounts.extend(wallets.iter().map(|wallet| AccountMeta::new_readonly(*wallet, false)));
+    accounts.extend(
+        wallets
+            .iter()
+            .map(|wallet| {
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(wallet, mint, token_program_id),
+                    false
+                )
+            })
+    );
+    accounts.extend(signer_account_metas(signer_pubkeys));
+    accounts.extend(option_account_metas(program_id, options));
+    accounts
 }
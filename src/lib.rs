@@ -2,6 +2,7 @@ pub mod program;
 pub mod error;
 pub mod event;
 pub mod instruction;
+pub mod offchain;
 pub mod processor;
 pub mod state;
 
@@ -0,0 +1,448 @@
+//! Offchain helpers that build token split instructions with the extra accounts
+//! required by Token-2022 transfer hooks
+
+pub use spl_transfer_hook_interface::offchain::{
+    AccountDataResult,
+    AccountFetchError
+};
+use {
+    solana_program::{
+        instruction::Instruction,
+        program_error::ProgramError,
+        pubkey::Pubkey
+    },
+    spl_token_2022::{
+        instruction::transfer_checked,
+        offchain::resolve_extra_transfer_account_metas
+    },
+    std::future::Future,
+    crate::{
+        error::SplitterError,
+        instruction::{
+            split_mixed_assets,
+            split_spl_tokens_by_routes,
+            split_spl_tokens_from_multiple_mints,
+            split_spl_tokens_from_single_mint,
+            split_spl_tokens_from_single_mint_by_shares,
+            split_spl_tokens_from_single_mint_by_shares_to_wallets,
+            split_spl_tokens_from_single_mint_to_wallets,
+            SplitLeg,
+            TokenSplitOptions,
+            TransferRoute
+        }
+    }
+};
+
+/// Same as `instruction::split_spl_tokens_from_single_mint`, with the extra accounts of
+/// the mint's transfer hook appended.
+///
+/// To stay client-agnostic this takes a function returning the data of the given
+/// address, the same way `spl_token_2022::offchain` does.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_from_single_mint_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    let routes = single_mint_routes(&amounts, decimals, 2)?;
+    let mut instruction = split_spl_tokens_from_single_mint(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        mint,
+        source,
+        destinations,
+        amounts,
+        decimals,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_spl_tokens_from_single_mint_by_shares`, with the extra
+/// accounts of the mint's transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_from_single_mint_by_shares_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    destinations: &[Pubkey],
+    total: u64,
+    shares: Vec<u64>,
+    sweep: bool,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    // the allocated amounts are only known onchain, the hooks are resolved per leg with `total`
+    let routes = single_mint_routes(&vec![total; shares.len()], decimals, 2)?;
+    let mut instruction = split_spl_tokens_from_single_mint_by_shares(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        mint,
+        source,
+        destinations,
+        total,
+        shares,
+        sweep,
+        decimals,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_spl_tokens_from_single_mint_to_wallets`, with the extra
+/// accounts of the mint's transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_from_single_mint_to_wallets_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    rent_payer: &Pubkey,
+    wallets: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    // the associated token accounts follow the rent payer, both programs and the wallets
+    let routes = single_mint_routes(&amounts, decimals, 5 + wallets.len())?;
+    let mut instruction = split_spl_tokens_from_single_mint_to_wallets(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        mint,
+        source,
+        rent_payer,
+        wallets,
+        amounts,
+        decimals,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_spl_tokens_from_single_mint_by_shares_to_wallets`, with
+/// the extra accounts of the mint's transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_from_single_mint_by_shares_to_wallets_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    mint: &Pubkey,
+    source: &Pubkey,
+    rent_payer: &Pubkey,
+    wallets: &[Pubkey],
+    total: u64,
+    shares: Vec<u64>,
+    sweep: bool,
+    decimals: u8,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    let routes = single_mint_routes(&vec![total; shares.len()], decimals, 5 + wallets.len())?;
+    let mut instruction = split_spl_tokens_from_single_mint_by_shares_to_wallets(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        mint,
+        source,
+        rent_payer,
+        wallets,
+        total,
+        shares,
+        sweep,
+        decimals,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_spl_tokens_from_multiple_mints`, with the extra accounts
+/// of every mint's transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_from_multiple_mints_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    sources: &[Pubkey],
+    destinations: &[Pubkey],
+    mints: &[Pubkey],
+    amounts: Vec<u64>,
+    decimals: Vec<u8>,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    // source `i` is paired with destination `m + i` and mint `2m + i` of the remaining accounts
+    let m = sources.len();
+    let routes = amounts
+        .iter()
+        .zip(decimals.iter())
+        .enumerate()
+        .map(|(index, (amount, decimals))| {
+            let route_index = |offset: usize| {
+                u16::try_from(offset + index).map_err(|_| SplitterError::InvalidMParameter)
+            };
+
+            Ok(
+                TransferRoute {
+                    source_index: route_index(0)?,
+                    destination_index: route_index(m)?,
+                    mint_index: route_index(2 * m)?,
+                    amount: *amount,
+                    decimals: *decimals
+                }
+            )
+        })
+        .collect::<Result<Vec<TransferRoute>, ProgramError>>()?;
+    let mut instruction = split_spl_tokens_from_multiple_mints(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        sources,
+        destinations,
+        mints,
+        amounts,
+        decimals,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_spl_tokens_by_routes`, with the extra accounts of every
+/// mint's transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_spl_tokens_by_routes_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    route_accounts: &[Pubkey],
+    routes: Vec<TransferRoute>,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    let mut instruction = split_spl_tokens_by_routes(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        route_accounts,
+        routes.clone(),
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Same as `instruction::split_mixed_assets`, with the extra accounts of every mint's
+/// transfer hook appended.
+#[allow(clippy::too_many_arguments)]
+pub async fn split_mixed_assets_with_extra_metas<F, Fut>(
+    fetch_account_data_fn: F,
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    operator: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    leg_accounts: &[Pubkey],
+    legs: Vec<SplitLeg>,
+    options: TokenSplitOptions
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    // the system program sits at 0 of the remaining accounts, before the leg accounts
+    let shift_index = |index: u16| {
+        index
+            .checked_add(1u16)
+            .ok_or(ProgramError::from(SplitterError::RouteIndexOutOfBounds))
+    };
+    let routes = legs
+        .iter()
+        .filter_map(|leg| match leg {
+            SplitLeg::Token(route) => Some(route),
+            SplitLeg::Lamports { .. } => None
+        })
+        .map(|route| {
+            Ok(
+                TransferRoute {
+                    source_index: shift_index(route.source_index)?,
+                    destination_index: shift_index(route.destination_index)?,
+                    mint_index: shift_index(route.mint_index)?,
+                    ..route.clone()
+                }
+            )
+        })
+        .collect::<Result<Vec<TransferRoute>, ProgramError>>()?;
+    let mut instruction = split_mixed_assets(
+        program_id,
+        token_program_id,
+        operator,
+        signer_pubkeys,
+        leg_accounts,
+        legs,
+        options
+    )?;
+
+    add_extra_transfer_account_metas(&mut instruction, fetch_account_data_fn, &routes).await?;
+
+    Ok(instruction)
+}
+
+/// Resolves the transfer hook accounts of every route and appends the ones the
+/// instruction does not already carry.
+///
+/// Routes index into the accounts after the operator and the token program.
+pub async fn add_extra_transfer_account_metas<F, Fut>(
+    instruction: &mut Instruction,
+    fetch_account_data_fn: F,
+    routes: &[TransferRoute]
+) -> Result<(), AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>
+{
+    let (operator, token_program_id) = match instruction.accounts.get(0..2) {
+        Some([operator, token_program]) => (operator.pubkey, token_program.pubkey),
+        _ => return Err(ProgramError::NotEnoughAccountKeys.into())
+    };
+
+    for route in routes.iter() {
+        let route_account = |index: u16| {
+            instruction
+                .accounts
+                .get(2 + index as usize)
+                .map(|account| account.pubkey)
+                .ok_or(ProgramError::from(SplitterError::RouteIndexOutOfBounds))
+        };
+        let (source, destination, mint) = (
+            route_account(route.source_index)?,
+            route_account(route.destination_index)?,
+            route_account(route.mint_index)?
+        );
+
+        let mut transfer_instruction = transfer_checked(
+            &token_program_id,
+            &source,
+            &mint,
+            &destination,
+            &operator,
+            &[],
+            route.amount,
+            route.decimals
+        )?;
+        resolve_extra_transfer_account_metas(
+            &mut transfer_instruction,
+            &fetch_account_data_fn,
+            &mint
+        ).await?;
+
+        // the program looks the extra accounts up by key, so only missing ones are appended
+        for extra_account in transfer_instruction.accounts.into_iter().skip(4) {
+            match instruction
+                .accounts
+                .iter_mut()
+                .find(|account| account.pubkey == extra_account.pubkey)
+            {
+                Some(account) => {
+                    account.is_signer |= extra_account.is_signer;
+                    account.is_writable |= extra_account.is_writable;
+                },
+                None => instruction.accounts.push(extra_account)
+            };
+        };
+    };
+
+    Ok(())
+}
+
+/// Routes of a single-mint split, the mint and source sit at 0 and 1 of the remaining
+/// accounts and destination `i` at `first_destination_index + i`.
+fn single_mint_routes(
+    amounts: &[u64],
+    decimals: u8,
+    first_destination_index: usize
+) -> Result<Vec<TransferRoute>, ProgramError> {
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(index, amount)| {
+            let destination_index = u16::try_from(first_destination_index + index)
+                .map_err(|_| SplitterError::AccountsAmountsMismatch)?;
+
+            Ok(
+                TransferRoute {
+                    source_index: 1,
+                    destination_index,
+                    mint_index: 0,
+                    amount: *amount,
+                    decimals
+                }
+            )
+        })
+        .collect()
+}
@@ -1,6 +1,7 @@
 #![allow(warnings)]
 
 use {
+    borsh::BorshSerialize,
     solana_program::{
         account_info::{
             AccountInfo,
@@ -10,38 +11,192 @@ use {
         entrypoint::ProgramResult,
         msg,
         system_instruction::transfer as transfer_lamports,
-        program::invoke,
-        program_error::ProgramError
+        program::{
+            invoke,
+            invoke_signed,
+            set_return_data
+        },
+        program_error::ProgramError,
+        system_program,
+        sysvar::{
+            clock::Clock,
+            rent::Rent,
+            Sysvar
+        }
+    },
+    self::helper::{
+        allocate_by_shares,
+        apply_rent_exemption_policy,
+        authorize_upgrade_authority,
+        checked_sum,
+        create_program_account,
+        top_up_rent,
+        create_recipient_token_accounts,
+        emit_split_events,
+        gross_up_transfer_amount,
+        invoke_close_account,
+        invoke_memo,
+        invoke_token_transfer,
+        native_mint_id,
+        resolve_config,
+        resolve_memo_program,
+        resolve_route,
+        resolve_treasury_account,
+        take_destinations,
+        unpack_token_account,
+        update_config,
+        update_plan,
+        authorize_admin,
+        authorize_plan_authority,
+        close_program_account,
+        load_plan,
+        validate_plan_allocation,
+        validate_plan_destinations,
+        resolve_policy,
+        validate_policy_account,
+        find_pending_change,
+        validate_config_account,
+        validate_destinations,
+        validate_fee_basis_points,
+        validate_mint,
+        validate_operator,
+        validate_program_id,
+        validate_routes,
+        validate_signer,
+        validate_token_program,
+        validate_remaining_balances,
+        validate_starting_balances,
+        summarize_transfers,
+        validate_transfer_hook_accounts,
+        validate_writable
     },
-    spl_token::{
-        instruction::transfer as transfer_spl_tokens,
-        ID as token_program_id
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token_2022::{
+        instruction::sync_native,
+        native_mint
     },
     crate::{
-        instruction::SplitterInstruction,
+        state::{
+            get_config_address,
+            get_plan_address,
+            get_policy_address,
+            Config,
+            ConfigChange,
+            PlanAllocation,
+            PlanAsset,
+            PlanTombstone,
+            SplitPlan,
+            PLAN_SEED,
+            PendingChange,
+            Policy,
+            PolicyKind,
+            SplitPolicy,
+            CONFIG_SEED,
+            MAX_PENDING_CHANGES,
+            MAX_POLICY_ADDRESSES
+        },
+        instruction::{
+            LamportSplitOptions,
+            PlanSplitOptions,
+            SplitLeg,
+            SplitMemo,
+            SplitQuote,
+            SplitterInstruction,
+            TokenSplitOptions,
+            TransferRoute
+        },
         error::SplitterError
     }
 };
 
+/// A transfer made by a split
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutedTransfer {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    /// Mint of the tokens, `None` for lamports
+    pub mint: Option<Pubkey>,
+    /// Lamports or raw token amount moved out of the source, transfer fee included
+    pub amount: u64,
+    /// Set on the transfer of the protocol fee to the treasury
+    pub protocol_fee: bool
+}
+
 pub struct Processor {}
 impl Processor {
     pub fn process_split_lamports(
         accounts: &[AccountInfo],
-        amounts: &Vec<u64>
-    ) -> ProgramResult {
+        amounts: &Vec<u64>,
+        options: &LamportSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
         let accounts_info = &mut accounts.iter();
 
         let source_account = next_account_info(accounts_info)?;
         let system_program_info = next_account_info(accounts_info)?;
+        let destination_accounts = take_destinations(accounts_info.as_slice(), amounts)?;
 
-        for amount in amounts.iter() {
-            let destination_account = next_account_info(accounts_info)?;
+        // pre-flight validation, nothing is transferred unless every account checks out
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_signer(source_account)?;
+        validate_writable(source_account)?;
+        validate_destinations(&[source_account], destination_accounts)?;
+        for destination_account in destination_accounts.iter() {
+            policy.validate_recipient(destination_account.key)?;
+        };
+        let memo_program = resolve_memo_program(accounts, &options.memo, amounts.len())?;
+
+        // the protocol fee is deducted before the rent-exemption policy applies, destinations
+        // skipped by the policy are left out and pay no fee
+        let rent = Rent::get()?;
+        let mut transfers: Vec<(usize, &AccountInfo, u64)> = Vec::with_capacity(amounts.len());
+        let mut protocol_fee = 0u64;
+        for (index, (destination_account, amount)) in destination_accounts.iter().zip(amounts.iter()).enumerate() {
+            let fee = config.fee_of(*amount);
+            let net_amount = amount
+                .checked_sub(fee)
+                .ok_or(SplitterError::AmountOverflow)?;
+            if let Some(amount) = apply_rent_exemption_policy(
+                destination_account,
+                net_amount,
+                &options.rent_exemption,
+                &rent
+            )? {
+                transfers.push((index, destination_account, amount));
+                protocol_fee = protocol_fee
+                    .checked_add(fee)
+                    .ok_or(SplitterError::AmountOverflow)?;
+            };
+        };
+        let treasury = if protocol_fee > 0 {
+            let treasury = resolve_treasury_account(accounts, &config.treasury)?;
+            validate_writable(treasury)?;
+
+            Some(treasury)
+        } else {
+            None
+        };
+
+        let mut transfer_amounts = transfers
+            .iter()
+            .map(|(_, _, amount)| *amount)
+            .collect::<Vec<u64>>();
+        transfer_amounts.push(protocol_fee);
+        if source_account.lamports() < checked_sum(&transfer_amounts)? {
+            return Err(
+                SplitterError::InsufficientLamports.into()
+            );
+        };
 
+        let mut executed_transfers: Vec<ExecutedTransfer> = Vec::with_capacity(transfers.len());
+        for (index, destination_account, amount) in transfers.into_iter() {
+            invoke_memo(memo_program, &options.memo, index)?;
             invoke(
                 &transfer_lamports(
                     &source_account.key,
                     &destination_account.key,
-                    *amount
+                    amount
                 ),
                 &[
                     source_account.clone(),
@@ -49,144 +204,2985 @@ impl Processor {
                     system_program_info.clone()
                 ]
             )?;
+
+            executed_transfers.push(
+                ExecutedTransfer {
+                    source: *source_account.key,
+                    destination: *destination_account.key,
+                    mint: None,
+                    amount,
+                    protocol_fee: false
+                }
+            );
         };
 
-        Ok(())
+        if let Some(treasury) = treasury {
+            invoke(
+                &transfer_lamports(
+                    &source_account.key,
+                    &treasury.key,
+                    protocol_fee
+                ),
+                &[
+                    source_account.clone(),
+                    treasury.clone(),
+                    system_program_info.clone()
+                ]
+            )?;
+
+            executed_transfers.push(
+                ExecutedTransfer {
+                    source: *source_account.key,
+                    destination: *treasury.key,
+                    mint: None,
+                    amount: protocol_fee,
+                    protocol_fee: true
+                }
+            );
+        };
+
+        Ok(executed_transfers)
     }
 
-    pub fn process_split_spl_tokens_from_single_mint(
+    pub fn process_split_lamports_by_shares(
         accounts: &[AccountInfo],
-        amounts: &Vec<u64>
-    ) -> ProgramResult {
+        total: &u64,
+        shares: &Vec<u64>,
+        options: &LamportSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let amounts = allocate_by_shares(*total, shares)?;
+
+        Self::process_split_lamports(
+            accounts,
+            &amounts,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_lamports_to_wrapped_sol(
+        accounts: &[AccountInfo],
+        amounts: &Vec<u64>,
+        options: &LamportSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
         let accounts_info = &mut accounts.iter();
 
-        let operator = next_account_info(accounts_info)?;
+        let source_account = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
         let token_program = next_account_info(accounts_info)?;
-        let source_ata = next_account_info(accounts_info)?;
+        let destination_atas = take_destinations(accounts_info.as_slice(), amounts)?;
 
-        for amount in amounts.iter() {
-            let destination_ata = next_account_info(accounts_info)?;
+        validate_token_program(token_program)?;
+        let native_mint = native_mint_id(token_program.key);
+        policy.validate_mint(&native_mint)?;
+        for destination_ata in destination_atas.iter() {
+            let destination = unpack_token_account(destination_ata, &native_mint, token_program.key)?;
+            policy.validate_recipient(&destination.owner)?;
+        };
+
+        // the destinations are followed by the trailing accounts, the memo program included
+        let lamports_accounts = [source_account, system_program_info]
+            .into_iter()
+            .chain(accounts_info.as_slice())
+            .cloned()
+            .collect::<Vec<AccountInfo>>();
+        let executed_transfers = Self::process_split_lamports(
+            &lamports_accounts,
+            amounts,
+            options,
+            config,
+            policy
+        )?;
 
+        for destination_ata in destination_atas.iter() {
             invoke(
-                &transfer_spl_tokens(
-                    &token_program.key,
-                    &source_ata.key,
-                    &destination_ata.key,
-                    &operator.key,
-                    &[],
-                    *amount
+                &sync_native(
+                    token_program.key,
+                    destination_ata.key
                 )?,
-                &[
-                    source_ata.clone(),
-                    destination_ata.clone(),
-                    operator.clone()
-                ]
+                &[destination_ata.clone()]
             )?;
         };
 
-        Ok(())
+        Ok(executed_transfers)
     }
 
-    pub fn process_split_spl_tokens_from_multiple_mints(
+    pub fn process_split_wrapped_sol_to_lamports(
         accounts: &[AccountInfo],
         amounts: &Vec<u64>,
-        m: &u16
-    ) -> ProgramResult {
-        let operator = &accounts[0];
-        let token_program = &accounts[1];
+        options: &LamportSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let accounts_info = &mut accounts.iter();
+
+        let operator = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+        let token_program = next_account_info(accounts_info)?;
+        let native_mint = next_account_info(accounts_info)?;
+        let source_ata = next_account_info(accounts_info)?;
+        let scratch_ata = next_account_info(accounts_info)?;
+        let destination_accounts = take_destinations(accounts_info.as_slice(), amounts)?;
 
-        let expected_accounts_len = ((m * 2) + 2) as usize;
-        if accounts.len() != expected_accounts_len {
+        validate_token_program(token_program)?;
+        if native_mint.key != &native_mint_id(token_program.key) {
             return Err(
-                ProgramError::Custom(
-                    SplitterError::InvalidMParameter(
-                        *m,
-                        expected_accounts_len,
-                        accounts.len()
-                    ).into()
+                SplitterError::InvalidMint.into()
+            );
+        };
+        // the lamport destinations are checked before the wrapped SOL is moved
+        policy.validate_mint(native_mint.key)?;
+        for destination_account in destination_accounts.iter() {
+            policy.validate_recipient(destination_account.key)?;
+        };
+
+        // 1. move the wrapped SOL to the scratch account and unwrap it to the operator, the
+        // protocol fee is left to the lamport legs so it is charged once, on the amounts the
+        // recipients are paid
+        let token_accounts = [operator, token_program, native_mint, source_ata, scratch_ata]
+            .into_iter()
+            .cloned()
+            .collect::<Vec<AccountInfo>>();
+        Self::process_split_spl_tokens_from_single_mint(
+            &token_accounts,
+            &vec![checked_sum(amounts)?],
+            &native_mint::DECIMALS,
+            &TokenSplitOptions::default(),
+            &Config::default(),
+            policy
+        )?;
+
+        invoke_close_account(
+            token_program,
+            scratch_ata,
+            operator,
+            operator,
+            &[]
+        )?;
+
+        // 2. split the unwrapped lamports from the operator
+        let lamports_accounts = [operator, system_program_info]
+            .into_iter()
+            .chain(accounts_info.as_slice())
+            .cloned()
+            .collect::<Vec<AccountInfo>>();
+        Self::process_split_lamports(
+            &lamports_accounts,
+            amounts,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_from_single_mint(
+        accounts: &[AccountInfo],
+        amounts: &Vec<u64>,
+        decimals: &u8,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let route_accounts = accounts
+            .get(2..)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        take_destinations(
+            route_accounts
+                .get(2..)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?,
+            amounts
+        )?;
+
+        // the mint and source sit at 0 and 1 of the remaining accounts, destination `i` at `2 + i`
+        let routes = amounts
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| {
+                let destination_index = index
+                    .checked_add(2usize)
+                    .and_then(|index| u16::try_from(index).ok())
+                    .ok_or(SplitterError::AccountsAmountsMismatch)?;
+
+                Ok(
+                    TransferRoute {
+                        source_index: 1,
+                        destination_index,
+                        mint_index: 0,
+                        amount: *amount,
+                        decimals: *decimals
+                    }
                 )
+            })
+            .collect::<Result<Vec<TransferRoute>, ProgramError>>()?;
+
+        Self::process_split_spl_tokens_by_routes(
+            accounts,
+            &routes,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_from_single_mint_by_shares(
+        accounts: &[AccountInfo],
+        total: &u64,
+        shares: &Vec<u64>,
+        sweep: &bool,
+        decimals: &u8,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let total = if *sweep {
+            let (token_program, mint, source_ata) = match accounts.get(1..4) {
+                Some([token_program, mint, source_ata]) => (token_program, mint, source_ata),
+                _ => return Err(ProgramError::NotEnoughAccountKeys)
+            };
+
+            unpack_token_account(source_ata, mint.key, token_program.key)?.amount
+        } else {
+            *total
+        };
+
+        let amounts = allocate_by_shares(total, shares)?;
+
+        Self::process_split_spl_tokens_from_single_mint(
+            accounts,
+            &amounts,
+            decimals,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_from_single_mint_to_wallets(
+        accounts: &[AccountInfo],
+        amounts: &Vec<u64>,
+        decimals: &u8,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let accounts = create_recipient_token_accounts(accounts, amounts.len(), *decimals, policy)?;
+
+        Self::process_split_spl_tokens_from_single_mint(
+            &accounts,
+            amounts,
+            decimals,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_from_single_mint_by_shares_to_wallets(
+        accounts: &[AccountInfo],
+        total: &u64,
+        shares: &Vec<u64>,
+        sweep: &bool,
+        decimals: &u8,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let accounts = create_recipient_token_accounts(accounts, shares.len(), *decimals, policy)?;
+
+        Self::process_split_spl_tokens_from_single_mint_by_shares(
+            &accounts,
+            total,
+            shares,
+            sweep,
+            decimals,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_from_multiple_mints(
+        accounts: &[AccountInfo],
+        amounts: &Vec<u64>,
+        m: &u16,
+        decimals: &Vec<u8>,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let expected_accounts_len = (*m as usize)
+            .checked_mul(3usize)
+            .and_then(|len| len.checked_add(2usize))
+            .ok_or(SplitterError::InvalidMParameter)?;
+        if accounts.len() < expected_accounts_len
+            || amounts.len() != *m as usize
+            || decimals.len() != *m as usize
+        {
+            msg!(
+                "Invalid m parameter. m={} and expected-accounts-len={} But provided-accounts-len={}, provided-amounts-len={} and provided-decimals-len={}",
+                m,
+                expected_accounts_len,
+                accounts.len(),
+                amounts.len(),
+                decimals.len()
+            );
+
+            return Err(
+                SplitterError::InvalidMParameter.into()
             );
         };
 
-        let source_atas = accounts
-            .get(2..=((*m + 1) as usize))
-            .unwrap();
-        let destination_atas = accounts
-            .get((m + 2) as usize..)
-            .unwrap();
+        // source `i` is paired with destination `m + i` and mint `2m + i` of the remaining accounts
+        let routes = amounts
+            .iter()
+            .zip(decimals.iter())
+            .enumerate()
+            .map(|(index, (amount, decimals))| {
+                let source_index = index as u16;
+                let destination_index = m
+                    .checked_add(source_index)
+                    .ok_or(SplitterError::InvalidMParameter)?;
+                let mint_index = destination_index
+                    .checked_add(*m)
+                    .ok_or(SplitterError::InvalidMParameter)?;
 
-        let mut count = 0usize;
-        for amount in amounts.iter() {
-            let source_ata = source_atas
-                .get(count)
-                .unwrap();
-            let destination_ata = destination_atas
-                .get(count)
-                .unwrap();
+                Ok(
+                    TransferRoute {
+                        source_index,
+                        destination_index,
+                        mint_index,
+                        amount: *amount,
+                        decimals: *decimals
+                    }
+                )
+            })
+            .collect::<Result<Vec<TransferRoute>, ProgramError>>()?;
 
-            invoke(
-                &transfer_spl_tokens(
-                    &token_program.key,
-                    &source_ata.key,
-                    &destination_ata.key,
-                    &operator.key,
-                    &[],
-                    *amount
-                )?,
-                &[
-                    source_ata.clone(),
-                    destination_ata.clone(),
-                    operator.clone()
-                ]
+        Self::process_split_spl_tokens_by_routes(
+            accounts,
+            &routes,
+            options,
+            config,
+            policy
+        )
+    }
+
+    pub fn process_split_spl_tokens_by_routes(
+        accounts: &[AccountInfo],
+        routes: &Vec<TransferRoute>,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let accounts_info = &mut accounts.iter();
+
+        let operator = next_account_info(accounts_info)?;
+        let token_program = next_account_info(accounts_info)?;
+        let route_accounts = accounts_info.as_slice();
+
+        // pre-flight validation, nothing is transferred unless every account checks out
+        validate_token_program(token_program)?;
+        let operator_signers = validate_operator(operator, token_program.key, accounts)?;
+        validate_routes(route_accounts, routes)?;
+        let close_destination = match options.close_sources_to {
+            Some(close_sources_to) => {
+                let close_destination = accounts
+                    .iter()
+                    .find(|account| account.key == &close_sources_to)
+                    .ok_or(SplitterError::MissingCloseDestination)?;
+                validate_writable(close_destination)?;
+
+                Some(close_destination)
+            },
+            None => None
+        };
+        let memo_program = resolve_memo_program(accounts, &options.memo, routes.len())?;
+
+        let epoch = if options.net_amounts {
+            Clock::get()?.epoch
+        } else {
+            0
+        };
+
+        // gross amount and expected fee of every route, the protocol fee is deducted first and
+        // amounts are grossed up when they are net
+        let mut transfer_amounts: Vec<(u64, Option<u64>)> = Vec::with_capacity(routes.len());
+        let mut source_totals: Vec<(&AccountInfo, &AccountInfo, u64)> = vec![];
+        let mut protocol_fees: Vec<(&AccountInfo, &AccountInfo, u8, u64)> = vec![];
+        for route in routes.iter() {
+            let (source_ata, destination_ata, mint) = resolve_route(route_accounts, route)?;
+            validate_mint(mint, token_program.key, route.decimals)?;
+            validate_transfer_hook_accounts(mint, accounts)?;
+            let destination = unpack_token_account(destination_ata, mint.key, token_program.key)?;
+            policy.validate_mint(mint.key)?;
+            policy.validate_recipient(destination_ata.key)?;
+            policy.validate_recipient(&destination.owner)?;
+
+            let protocol_fee = config.fee_of(route.amount);
+            let net_amount = route
+                .amount
+                .checked_sub(protocol_fee)
+                .ok_or(SplitterError::AmountOverflow)?;
+            let (amount, fee) = if options.net_amounts {
+                gross_up_transfer_amount(mint, net_amount, epoch)?
+            } else {
+                (net_amount, None)
+            };
+            transfer_amounts.push((amount, fee));
+
+            let source_total = amount
+                .checked_add(protocol_fee)
+                .ok_or(SplitterError::AmountOverflow)?;
+            match source_totals.iter_mut().find(|(source, _, _)| source.key == source_ata.key) {
+                Some((_, source_mint, total)) => {
+                    if source_mint.key != mint.key {
+                        return Err(
+                            SplitterError::MintMismatch.into()
+                        );
+                    };
+
+                    *total = total
+                        .checked_add(source_total)
+                        .ok_or(SplitterError::AmountOverflow)?;
+                },
+                None => source_totals.push((source_ata, mint, source_total))
+            };
+
+            if protocol_fee > 0 {
+                match protocol_fees.iter_mut().find(|(source, _, _, _)| source.key == source_ata.key) {
+                    Some((_, _, _, total)) => {
+                        *total = total
+                            .checked_add(protocol_fee)
+                            .ok_or(SplitterError::AmountOverflow)?;
+                    },
+                    None => protocol_fees.push((source_ata, mint, route.decimals, protocol_fee))
+                };
+            };
+        };
+        let mut fee_transfers: Vec<(&AccountInfo, &AccountInfo, u8, u64, &AccountInfo)> = Vec::with_capacity(protocol_fees.len());
+        for (source_ata, mint, decimals, protocol_fee) in protocol_fees.into_iter() {
+            let treasury_ata = resolve_treasury_account(
+                accounts,
+                &get_associated_token_address_with_program_id(&config.treasury, mint.key, token_program.key)
+            )?;
+            validate_writable(treasury_ata)?;
+            unpack_token_account(treasury_ata, mint.key, token_program.key)?;
+
+            fee_transfers.push((source_ata, mint, decimals, protocol_fee, treasury_ata));
+        };
+        for (source_ata, mint, total) in source_totals.iter() {
+            if unpack_token_account(source_ata, mint.key, token_program.key)?.amount < *total {
+                return Err(
+                    SplitterError::InsufficientTokens.into()
+                );
+            };
+        };
+
+        let mut executed_transfers: Vec<ExecutedTransfer> = Vec::with_capacity(routes.len());
+        for (index, (route, (amount, fee))) in routes.iter().zip(transfer_amounts.iter()).enumerate() {
+            let (source_ata, destination_ata, mint) = resolve_route(route_accounts, route)?;
+
+            invoke_memo(memo_program, &options.memo, index)?;
+            invoke_token_transfer(
+                token_program,
+                source_ata,
+                mint,
+                destination_ata,
+                operator,
+                &operator_signers,
+                accounts,
+                *amount,
+                route.decimals,
+                *fee
+            )?;
+
+            executed_transfers.push(
+                ExecutedTransfer {
+                    source: *source_ata.key,
+                    destination: *destination_ata.key,
+                    mint: Some(*mint.key),
+                    amount: *amount,
+                    protocol_fee: false
+                }
+            );
+        };
+        for (source_ata, mint, decimals, protocol_fee, treasury_ata) in fee_transfers.into_iter() {
+            invoke_token_transfer(
+                token_program,
+                source_ata,
+                mint,
+                treasury_ata,
+                operator,
+                &operator_signers,
+                accounts,
+                protocol_fee,
+                decimals,
+                None
             )?;
 
-            count = count
-                .checked_add(1usize)
-                .unwrap();
+            executed_transfers.push(
+                ExecutedTransfer {
+                    source: *source_ata.key,
+                    destination: *treasury_ata.key,
+                    mint: Some(*mint.key),
+                    amount: protocol_fee,
+                    protocol_fee: true
+                }
+            );
+        };
+
+        if let Some(close_destination) = close_destination {
+            for (source_ata, mint, _) in source_totals.iter() {
+                if unpack_token_account(source_ata, mint.key, token_program.key)?.amount == 0 {
+                    invoke_close_account(
+                        token_program,
+                        source_ata,
+                        close_destination,
+                        operator,
+                        &operator_signers
+                    )?;
+                };
+            };
+        };
+
+        Ok(executed_transfers)
+    }
+
+    pub fn process_split_mixed_assets(
+        accounts: &[AccountInfo],
+        legs: &Vec<SplitLeg>,
+        options: &TokenSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let operator = accounts
+            .get(0)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let system_program_info = accounts
+            .get(2)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let leg_accounts = &accounts[3..];
+
+        let mut routes: Vec<TransferRoute> = vec![];
+        let mut lamports_accounts: Vec<AccountInfo> = vec![
+            operator.clone(),
+            system_program_info.clone()
+        ];
+        let mut lamports_amounts: Vec<u64> = vec![];
+        let per_leg_memos = match &options.memo {
+            Some(SplitMemo::PerLeg(memos)) => {
+                if memos.len() != legs.len() {
+                    return Err(
+                        SplitterError::MemoLegsMismatch.into()
+                    );
+                };
+
+                Some(memos)
+            },
+            _ => None
+        };
+        let mut route_memos: Vec<String> = vec![];
+        let mut lamports_memos: Vec<String> = vec![];
+        for (index, leg) in legs.iter().enumerate() {
+            let leg_memo = per_leg_memos.map(|memos| memos[index].clone());
+
+            match leg {
+                SplitLeg::Lamports { destination_index, amount } => {
+                    let destination_account = leg_accounts
+                        .get(*destination_index as usize)
+                        .ok_or(SplitterError::RouteIndexOutOfBounds)?;
+
+                    lamports_accounts.push(destination_account.clone());
+                    lamports_amounts.push(*amount);
+                    lamports_memos.extend(leg_memo);
+                },
+                SplitLeg::Token(route) => {
+                    // the system program sits at 0 of the remaining accounts, before the leg accounts
+                    let shift_index = |index: u16| {
+                        index
+                            .checked_add(1u16)
+                            .ok_or(SplitterError::RouteIndexOutOfBounds)
+                    };
+
+                    routes.push(
+                        TransferRoute {
+                            source_index: shift_index(route.source_index)?,
+                            destination_index: shift_index(route.destination_index)?,
+                            mint_index: shift_index(route.mint_index)?,
+                            ..route.clone()
+                        }
+                    );
+                    route_memos.extend(leg_memo);
+                }
+            };
+        };
+        // lamport legs run after the token legs, check their recipients before any transfer
+        for destination_account in lamports_accounts[2..].iter() {
+            policy.validate_recipient(destination_account.key)?;
+        };
+        // the memo program is looked up in the trailing accounts
+        lamports_accounts.extend(leg_accounts.iter().cloned());
+
+        let (route_memo, lamports_memo) = match per_leg_memos {
+            Some(_) => (
+                Some(SplitMemo::PerLeg(route_memos)),
+                Some(SplitMemo::PerLeg(lamports_memos))
+            ),
+            None => (
+                options.memo.clone(),
+                options.memo.clone()
+            )
+        };
+
+        // the system program and the lamport source are checked before any token is moved
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        if !lamports_amounts.is_empty() {
+            validate_signer(operator)?;
+            validate_writable(operator)?;
+        };
+
+        let mut executed_transfers: Vec<ExecutedTransfer> = Vec::with_capacity(legs.len());
+        if !routes.is_empty() {
+            executed_transfers.extend(
+                Self::process_split_spl_tokens_by_routes(
+                    accounts,
+                    &routes,
+                    &TokenSplitOptions {
+                        memo: route_memo,
+                        ..options.clone()
+                    },
+                    config,
+                    policy
+                )?
+            );
+        };
+        if !lamports_amounts.is_empty() {
+            executed_transfers.extend(
+                Self::process_split_lamports(
+                    &lamports_accounts,
+                    &lamports_amounts,
+                    &LamportSplitOptions {
+                        memo: lamports_memo,
+                        ..LamportSplitOptions::default()
+                    },
+                    config,
+                    policy
+                )?
+            );
+        };
+
+        Ok(executed_transfers)
+    }
+
+    pub fn process_quote_split(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        total: &u64,
+        shares: &Vec<u64>,
+        sweep: &bool
+    ) -> ProgramResult {
+        let total = if *sweep {
+            let (token_program, mint, source_ata) = match accounts.get(0..3) {
+                Some([token_program, mint, source_ata]) => (token_program, mint, source_ata),
+                _ => return Err(ProgramError::NotEnoughAccountKeys)
+            };
+
+            validate_token_program(token_program)?;
+            unpack_token_account(source_ata, mint.key, token_program.key)?.amount
+        } else {
+            *total
+        };
+
+        // every leg pays the fee of its own amount, like in the splits
+        let config = resolve_config(program_id, accounts)?;
+        let mut amounts = allocate_by_shares(total, shares)?;
+        let mut fee = 0u64;
+        for amount in amounts.iter_mut() {
+            let leg_fee = config.fee_of(*amount);
+            *amount = amount
+                .checked_sub(leg_fee)
+                .ok_or(SplitterError::AmountOverflow)?;
+            fee = fee
+                .checked_add(leg_fee)
+                .ok_or(SplitterError::AmountOverflow)?;
         };
 
+        let quote = SplitQuote {
+            amounts,
+            total,
+            fee
+        };
+        set_return_data(&quote.try_to_vec()?);
+
         Ok(())
     }
 
-    pub fn process(
+    pub fn process_initialize_config(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        instruction_data: &[u8]
+        admin: &Pubkey,
+        fee_basis_points: &u16,
+        treasury: &Pubkey,
+        timelock_delay: &u32
     ) -> ProgramResult {
-        let instruction = SplitterInstruction::unpack(instruction_data).unwrap();
+        let accounts_info = &mut accounts.iter();
 
-        match instruction {
-            SplitterInstruction::SplitLamports(amounts) => {
-                msg!("Instruction: SplitLamports");
-                Self::process_split_lamports(
-                    accounts,
-                    &amounts
-                ).unwrap();
-            },
-            SplitterInstruction::SplitSplTokensFromSingleMint(amounts) => {
-                msg!("Instruction: SplitSplTokensFromSingleMint");
-                Self::process_split_spl_tokens_from_single_mint(
-                    accounts,
-                    &amounts
-                ).unwrap();
-            },
-            SplitterInstruction::SplitSplTokensFromMultipleMints(
-                amounts,
-                m
-            ) => {
-                msg!("Instruction: SplitSplTokensFromMultipleMints");
-                Self::process_split_spl_tokens_from_multiple_mints(
-                    accounts,
-                    &amounts,
-                    &m
-                ).unwrap();
-            }
+        let payer = next_account_info(accounts_info)?;
+        let upgrade_authority = next_account_info(accounts_info)?;
+        let program_data_info = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_signer(payer)?;
+        validate_writable(payer)?;
+        validate_writable(config_info)?;
+        // whoever initializes the config picks the admin, the fee and the treasury
+        authorize_upgrade_authority(program_id, upgrade_authority, program_data_info)?;
+
+        let (config_address, bump) = get_config_address(program_id);
+        if *config_info.key != config_address {
+            return Err(
+                SplitterError::InvalidConfig.into()
+            );
+        };
+        if config_info.owner == program_id {
+            return Err(
+                SplitterError::ConfigAlreadyInitialized.into()
+            );
+        };
+        validate_fee_basis_points(*fee_basis_points)?;
+
+        create_program_account(
+            program_id,
+            payer,
+            config_info,
+            system_program_info,
+            Config::LEN,
+            &[CONFIG_SEED, &[bump]]
+        )?;
+
+        let config = Config {
+            admin: *admin,
+            fee_basis_points: *fee_basis_points,
+            treasury: *treasury,
+            paused: false,
+            bump,
+            pending_admin: None,
+            timelock_delay: *timelock_delay,
+            next_change_id: 0u64,
+            pending_changes: vec![]
         };
+        config_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&config.pack()?);
 
         Ok(())
     }
-}
 
-mod helper {
-    //
+    pub fn process_propose_admin(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        admin: &Option<Pubkey>
+    ) -> ProgramResult {
+        update_config(program_id, accounts, |config| {
+            config.pending_admin = *admin;
+
+            Ok(())
+        })
+    }
+
+    pub fn process_accept_admin(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo]
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let new_admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+
+        validate_signer(new_admin)?;
+        validate_config_account(program_id, config_info)?;
+
+        let mut config = Config::unpack(&config_info.try_borrow_data()?)?;
+        if config.pending_admin != Some(*new_admin.key) {
+            return Err(
+                SplitterError::InvalidPendingAdmin.into()
+            );
+        };
+
+        config.admin = *new_admin.key;
+        config.pending_admin = None;
+        config_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&config.pack()?);
+
+        Ok(())
+    }
+
+    pub fn process_queue_config_change(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        change: &ConfigChange
+    ) -> ProgramResult {
+        update_config(program_id, accounts, |config| {
+            if let ConfigChange::Fee(fee_basis_points) = change {
+                validate_fee_basis_points(*fee_basis_points)?;
+            };
+            if config.pending_changes.len() >= MAX_PENDING_CHANGES {
+                return Err(
+                    SplitterError::TooManyPendingChanges.into()
+                );
+            };
+
+            let executable_at = Clock::get()?
+                .unix_timestamp
+                .checked_add(config.timelock_delay as i64)
+                .ok_or(SplitterError::AmountOverflow)?;
+
+            config.pending_changes.push(
+                PendingChange {
+                    id: config.next_change_id,
+                    change: change.clone(),
+                    executable_at
+                }
+            );
+            config.next_change_id = config
+                .next_change_id
+                .checked_add(1u64)
+                .ok_or(SplitterError::AmountOverflow)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn process_execute_config_change(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        id: &u64
+    ) -> ProgramResult {
+        update_config(program_id, accounts, |config| {
+            let position = find_pending_change(config, *id)?;
+            if Clock::get()?.unix_timestamp < config.pending_changes[position].executable_at {
+                return Err(
+                    SplitterError::TimelockNotElapsed.into()
+                );
+            };
+
+            match config.pending_changes.remove(position).change {
+                ConfigChange::Fee(fee_basis_points) => config.fee_basis_points = fee_basis_points,
+                ConfigChange::Treasury(treasury) => config.treasury = treasury,
+                ConfigChange::TimelockDelay(timelock_delay) => config.timelock_delay = timelock_delay
+            };
+
+            Ok(())
+        })
+    }
+
+    pub fn process_cancel_config_change(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        id: &u64
+    ) -> ProgramResult {
+        update_config(program_id, accounts, |config| {
+            let position = find_pending_change(config, *id)?;
+            config.pending_changes.remove(position);
+
+            Ok(())
+        })
+    }
+
+    pub fn process_set_paused(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        paused: &bool
+    ) -> ProgramResult {
+        update_config(program_id, accounts, |config| {
+            config.paused = *paused;
+
+            Ok(())
+        })
+    }
+
+    pub fn process_initialize_policy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: &PolicyKind
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let payer = next_account_info(accounts_info)?;
+        let admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+        let policy_info = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_signer(payer)?;
+        validate_writable(payer)?;
+        validate_writable(policy_info)?;
+        authorize_admin(program_id, admin, config_info)?;
+
+        let (policy_address, bump) = get_policy_address(program_id, *kind);
+        if *policy_info.key != policy_address || policy_info.owner == program_id {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        create_program_account(
+            program_id,
+            payer,
+            policy_info,
+            system_program_info,
+            Policy::space(0),
+            &[kind.seed(), &[bump]]
+        )?;
+
+        let policy = Policy {
+            kind: *kind,
+            bump,
+            addresses: vec![]
+        };
+        policy_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&policy.pack()?);
+
+        Ok(())
+    }
+
+    pub fn process_add_policy_addresses(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: &PolicyKind,
+        addresses: &Vec<Pubkey>
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+        let policy_info = next_account_info(accounts_info)?;
+        let payer = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_signer(payer)?;
+        validate_writable(payer)?;
+        authorize_admin(program_id, admin, config_info)?;
+        validate_policy_account(program_id, policy_info, *kind)?;
+
+        for address in addresses.iter() {
+            let (len, position) = {
+                let policy_data = policy_info.try_borrow_data()?;
+                let (_, len) = Policy::unpack_header(&policy_data)?;
+                match Policy::search(&policy_data, address)? {
+                    Ok(_) => continue,
+                    Err(position) => (len, position)
+                }
+            };
+            if len >= MAX_POLICY_ADDRESSES {
+                return Err(
+                    SplitterError::PolicyFull.into()
+                );
+            };
+
+            // the account grows by an address and the ones after the position move up
+            policy_info.realloc(Policy::space(len + 1), false)?;
+            let mut policy_data = policy_info.try_borrow_mut_data()?;
+            let offset = Policy::space(position);
+            policy_data.copy_within(offset..Policy::space(len), offset + 32);
+            policy_data[offset..offset + 32].copy_from_slice(address.as_ref());
+            Policy::pack_len(&mut policy_data, len + 1);
+        };
+
+        top_up_rent(payer, policy_info, system_program_info, policy_info.data_len())
+    }
+
+    pub fn process_remove_policy_addresses(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: &PolicyKind,
+        addresses: &Vec<Pubkey>
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+        let policy_info = next_account_info(accounts_info)?;
+
+        authorize_admin(program_id, admin, config_info)?;
+        validate_policy_account(program_id, policy_info, *kind)?;
+
+        for address in addresses.iter() {
+            let (len, position) = {
+                let policy_data = policy_info.try_borrow_data()?;
+                let (_, len) = Policy::unpack_header(&policy_data)?;
+                match Policy::search(&policy_data, address)? {
+                    Ok(position) => (len, position),
+                    Err(_) => continue
+                }
+            };
+
+            // the addresses after the position move down and the account shrinks by one, the
+            // rent it no longer needs is reclaimed when the policy is closed
+            {
+                let mut policy_data = policy_info.try_borrow_mut_data()?;
+                let offset = Policy::space(position);
+                policy_data.copy_within(offset + 32..Policy::space(len), offset);
+                Policy::pack_len(&mut policy_data, len - 1);
+            };
+            policy_info.realloc(Policy::space(len - 1), false)?;
+        };
+
+        Ok(())
+    }
+
+    pub fn process_close_policy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: &PolicyKind
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+        let policy_info = next_account_info(accounts_info)?;
+        let receiver = next_account_info(accounts_info)?;
+
+        authorize_admin(program_id, admin, config_info)?;
+        validate_policy_account(program_id, policy_info, *kind)?;
+        validate_writable(receiver)?;
+
+        // the policy reads as not initialized once closed
+        close_program_account(policy_info, receiver)
+    }
+
+    pub fn process_create_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        id: &u64,
+        asset: &PlanAsset,
+        recipients: &Vec<Pubkey>,
+        allocation: &PlanAllocation
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let payer = next_account_info(accounts_info)?;
+        let authority = next_account_info(accounts_info)?;
+        let plan_info = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_signer(payer)?;
+        validate_writable(payer)?;
+        validate_signer(authority)?;
+        validate_writable(plan_info)?;
+        validate_plan_allocation(recipients, allocation)?;
+
+        let (plan_address, bump) = get_plan_address(program_id, authority.key, *id);
+        if *plan_info.key != plan_address {
+            return Err(
+                SplitterError::InvalidPlan.into()
+            );
+        };
+
+        let version = if plan_info.owner == program_id {
+            // only the tombstone of a closed plan can be replaced, the new plan carries on
+            // from its version so executions signed for the closed plan never match
+            let tombstone = PlanTombstone::unpack(&plan_info.try_borrow_data()?)?;
+            plan_info.realloc(SplitPlan::LEN, true)?;
+            top_up_rent(payer, plan_info, system_program_info, SplitPlan::LEN)?;
+
+            tombstone
+                .version
+                .checked_add(1u64)
+                .ok_or(SplitterError::AmountOverflow)?
+        } else {
+            create_program_account(
+                program_id,
+                payer,
+                plan_info,
+                system_program_info,
+                SplitPlan::LEN,
+                &[PLAN_SEED, authority.key.as_ref(), &id.to_le_bytes(), &[bump]]
+            )?;
+
+            0u64
+        };
+
+        let plan = SplitPlan {
+            authority: *authority.key,
+            id: *id,
+            bump,
+            asset: asset.clone(),
+            recipients: recipients.clone(),
+            allocation: allocation.clone(),
+            version,
+            locked: false
+        };
+        plan_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&plan.pack()?);
+
+        Ok(())
+    }
+
+    pub fn process_update_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        recipients: &Vec<Pubkey>,
+        allocation: &PlanAllocation
+    ) -> ProgramResult {
+        update_plan(program_id, accounts, |plan| {
+            validate_plan_allocation(recipients, allocation)?;
+            plan.recipients = recipients.clone();
+            plan.allocation = allocation.clone();
+
+            Ok(())
+        })
+    }
+
+    pub fn process_close_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo]
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_info)?;
+        let plan_info = next_account_info(accounts_info)?;
+        let receiver = next_account_info(accounts_info)?;
+
+        let plan = authorize_plan_authority(program_id, authority, plan_info)?;
+        validate_writable(plan_info)?;
+        validate_writable(receiver)?;
+        // a locked plan closed and created again would pay other recipients at the same address
+        if plan.locked {
+            return Err(
+                SplitterError::PlanLocked.into()
+            );
+        };
+
+        // the tombstone keeps the version, the rent it does not need goes to the receiver
+        let tombstone = PlanTombstone {
+            version: plan.version
+        };
+        plan_info.realloc(PlanTombstone::LEN, false)?;
+        plan_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&tombstone.pack()?);
+
+        let released_lamports = plan_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(PlanTombstone::LEN));
+        **plan_info.try_borrow_mut_lamports()? -= released_lamports;
+        **receiver.try_borrow_mut_lamports()? = receiver
+            .lamports()
+            .checked_add(released_lamports)
+            .ok_or(SplitterError::AmountOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn process_lock_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo]
+    ) -> ProgramResult {
+        update_plan(program_id, accounts, |plan| {
+            plan.locked = true;
+
+            Ok(())
+        })
+    }
+
+    pub fn process_execute_plan(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        total: &u64,
+        expected_version: &u64,
+        options: &PlanSplitOptions,
+        config: &Config,
+        policy: &SplitPolicy
+    ) -> Result<Vec<ExecutedTransfer>, ProgramError> {
+        let plan_info = accounts
+            .get(1)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let plan = load_plan(program_id, plan_info)?;
+        if plan.version != *expected_version {
+            msg!(
+                "Split plan is at version {} but version {} was expected",
+                plan.version,
+                expected_version
+            );
+
+            return Err(
+                SplitterError::PlanVersionMismatch.into()
+            );
+        };
+
+        // without the plan, the accounts are those of the split the plan runs
+        let split_accounts = accounts[..1]
+            .iter()
+            .chain(&accounts[2..])
+            .cloned()
+            .collect::<Vec<AccountInfo>>();
+
+        match (&plan.asset, options) {
+            (PlanAsset::Lamports, PlanSplitOptions::Lamports(options)) => {
+                validate_plan_destinations(&plan, split_accounts.get(2..).unwrap_or_default())?;
+
+                match &plan.allocation {
+                    PlanAllocation::Shares(shares) => Self::process_split_lamports_by_shares(
+                        &split_accounts,
+                        total,
+                        shares,
+                        options,
+                        config,
+                        policy
+                    ),
+                    PlanAllocation::Amounts(amounts) => Self::process_split_lamports(
+                        &split_accounts,
+                        amounts,
+                        options,
+                        config,
+                        policy
+                    )
+                }
+            },
+            (PlanAsset::Token { mint, decimals }, PlanSplitOptions::Token(options)) => {
+                let mint_info = split_accounts
+                    .get(2)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                if mint_info.key != mint {
+                    return Err(
+                        SplitterError::MintMismatch.into()
+                    );
+                };
+                validate_plan_destinations(&plan, split_accounts.get(4..).unwrap_or_default())?;
+
+                match &plan.allocation {
+                    PlanAllocation::Shares(shares) => Self::process_split_spl_tokens_from_single_mint_by_shares(
+                        &split_accounts,
+                        total,
+                        shares,
+                        &false,
+                        decimals,
+                        options,
+                        config,
+                        policy
+                    ),
+                    PlanAllocation::Amounts(amounts) => Self::process_split_spl_tokens_from_single_mint(
+                        &split_accounts,
+                        amounts,
+                        decimals,
+                        options,
+                        config,
+                        policy
+                    )
+                }
+            },
+            _ => Err(
+                SplitterError::PlanOptionsMismatch.into()
+            )
+        }
+    }
+
+    pub fn process(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8]
+    ) -> ProgramResult {
+        let instruction = SplitterInstruction::unpack(instruction_data)?;
+        let kind = instruction.kind();
+        let guards = instruction.guards().to_vec();
+
+        let (config, policy) = match kind {
+            Some(_) => (
+                resolve_config(program_id, accounts)?,
+                SplitPolicy {
+                    mint_allowlist: resolve_policy(program_id, accounts, PolicyKind::MintAllowlist)?,
+                    recipient_denylist: resolve_policy(program_id, accounts, PolicyKind::RecipientDenylist)?
+                }
+            ),
+            None => (
+                Config::default(),
+                SplitPolicy::default()
+            )
+        };
+        if config.paused {
+            return Err(
+                SplitterError::SplitsPaused.into()
+            );
+        };
+
+        validate_starting_balances(accounts, &guards)?;
+
+        let executed_transfers = match instruction {
+            SplitterInstruction::SplitLamports(
+                amounts,
+                options
+            ) => {
+                msg!("Instruction: SplitLamports");
+                Self::process_split_lamports(
+                    accounts,
+                    &amounts,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensFromSingleMint(
+                amounts,
+                decimals,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensFromSingleMint");
+                Self::process_split_spl_tokens_from_single_mint(
+                    accounts,
+                    &amounts,
+                    &decimals,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensFromMultipleMints(
+                amounts,
+                m,
+                decimals,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensFromMultipleMints");
+                Self::process_split_spl_tokens_from_multiple_mints(
+                    accounts,
+                    &amounts,
+                    &m,
+                    &decimals,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensByRoutes(
+                routes,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensByRoutes");
+                Self::process_split_spl_tokens_by_routes(
+                    accounts,
+                    &routes,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitLamportsByShares(
+                total,
+                shares,
+                options
+            ) => {
+                msg!("Instruction: SplitLamportsByShares");
+                Self::process_split_lamports_by_shares(
+                    accounts,
+                    &total,
+                    &shares,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensFromSingleMintByShares(
+                total,
+                shares,
+                sweep,
+                decimals,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensFromSingleMintByShares");
+                Self::process_split_spl_tokens_from_single_mint_by_shares(
+                    accounts,
+                    &total,
+                    &shares,
+                    &sweep,
+                    &decimals,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensFromSingleMintToWallets(
+                amounts,
+                decimals,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensFromSingleMintToWallets");
+                Self::process_split_spl_tokens_from_single_mint_to_wallets(
+                    accounts,
+                    &amounts,
+                    &decimals,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitSplTokensFromSingleMintBySharesToWallets(
+                total,
+                shares,
+                sweep,
+                decimals,
+                options
+            ) => {
+                msg!("Instruction: SplitSplTokensFromSingleMintBySharesToWallets");
+                Self::process_split_spl_tokens_from_single_mint_by_shares_to_wallets(
+                    accounts,
+                    &total,
+                    &shares,
+                    &sweep,
+                    &decimals,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitLamportsToWrappedSol(
+                amounts,
+                options
+            ) => {
+                msg!("Instruction: SplitLamportsToWrappedSol");
+                Self::process_split_lamports_to_wrapped_sol(
+                    accounts,
+                    &amounts,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitWrappedSolToLamports(
+                amounts,
+                options
+            ) => {
+                msg!("Instruction: SplitWrappedSolToLamports");
+                Self::process_split_wrapped_sol_to_lamports(
+                    accounts,
+                    &amounts,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::SplitMixedAssets(
+                legs,
+                options
+            ) => {
+                msg!("Instruction: SplitMixedAssets");
+                Self::process_split_mixed_assets(
+                    accounts,
+                    &legs,
+                    &options,
+                    &config,
+                    &policy
+                )
+            },
+            SplitterInstruction::QuoteSplit(
+                total,
+                shares,
+                sweep
+            ) => {
+                msg!("Instruction: QuoteSplit");
+                Self::process_quote_split(
+                    program_id,
+                    accounts,
+                    &total,
+                    &shares,
+                    &sweep
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::InitializeConfig(
+                admin,
+                fee_basis_points,
+                treasury,
+                timelock_delay
+            ) => {
+                msg!("Instruction: InitializeConfig");
+                Self::process_initialize_config(
+                    program_id,
+                    accounts,
+                    &admin,
+                    &fee_basis_points,
+                    &treasury,
+                    &timelock_delay
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::ProposeAdmin(admin) => {
+                msg!("Instruction: ProposeAdmin");
+                Self::process_propose_admin(
+                    program_id,
+                    accounts,
+                    &admin
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::AcceptAdmin => {
+                msg!("Instruction: AcceptAdmin");
+                Self::process_accept_admin(
+                    program_id,
+                    accounts
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::QueueConfigChange(change) => {
+                msg!("Instruction: QueueConfigChange");
+                Self::process_queue_config_change(
+                    program_id,
+                    accounts,
+                    &change
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::ExecuteConfigChange(id) => {
+                msg!("Instruction: ExecuteConfigChange");
+                Self::process_execute_config_change(
+                    program_id,
+                    accounts,
+                    &id
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::CancelConfigChange(id) => {
+                msg!("Instruction: CancelConfigChange");
+                Self::process_cancel_config_change(
+                    program_id,
+                    accounts,
+                    &id
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::SetPaused(paused) => {
+                msg!("Instruction: SetPaused");
+                Self::process_set_paused(
+                    program_id,
+                    accounts,
+                    &paused
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::InitializePolicy(kind) => {
+                msg!("Instruction: InitializePolicy");
+                Self::process_initialize_policy(
+                    program_id,
+                    accounts,
+                    &kind
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::AddPolicyAddresses(
+                kind,
+                addresses
+            ) => {
+                msg!("Instruction: AddPolicyAddresses");
+                Self::process_add_policy_addresses(
+                    program_id,
+                    accounts,
+                    &kind,
+                    &addresses
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::RemovePolicyAddresses(
+                kind,
+                addresses
+            ) => {
+                msg!("Instruction: RemovePolicyAddresses");
+                Self::process_remove_policy_addresses(
+                    program_id,
+                    accounts,
+                    &kind,
+                    &addresses
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::ClosePolicy(kind) => {
+                msg!("Instruction: ClosePolicy");
+                Self::process_close_policy(
+                    program_id,
+                    accounts,
+                    &kind
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::CreatePlan(
+                id,
+                asset,
+                recipients,
+                allocation
+            ) => {
+                msg!("Instruction: CreatePlan");
+                Self::process_create_plan(
+                    program_id,
+                    accounts,
+                    &id,
+                    &asset,
+                    &recipients,
+                    &allocation
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::UpdatePlan(
+                recipients,
+                allocation
+            ) => {
+                msg!("Instruction: UpdatePlan");
+                Self::process_update_plan(
+                    program_id,
+                    accounts,
+                    &recipients,
+                    &allocation
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::ClosePlan => {
+                msg!("Instruction: ClosePlan");
+                Self::process_close_plan(
+                    program_id,
+                    accounts
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::LockPlan => {
+                msg!("Instruction: LockPlan");
+                Self::process_lock_plan(
+                    program_id,
+                    accounts
+                )?;
+
+                Ok(vec![])
+            },
+            SplitterInstruction::ExecutePlan(
+                total,
+                expected_version,
+                options
+            ) => {
+                msg!("Instruction: ExecutePlan");
+                Self::process_execute_plan(
+                    program_id,
+                    accounts,
+                    &total,
+                    &expected_version,
+                    &options,
+                    &config,
+                    &policy
+                )
+            }
+        }?;
+
+        validate_remaining_balances(accounts, &guards)?;
+
+        if let Some(kind) = kind {
+            let summary = summarize_transfers(kind, &executed_transfers)?;
+            emit_split_events(&summary, &executed_transfers)?;
+            set_return_data(&summary.try_to_vec()?);
+        };
+
+        Ok(())
+    }
+}
+
+mod helper {
+    use {
+        solana_program::{
+            account_info::{
+                AccountInfo,
+                next_account_info
+            },
+            entrypoint::ProgramResult,
+            bpf_loader_upgradeable::{
+                self,
+                UpgradeableLoaderState
+            },
+            hash::hashv,
+            msg,
+            program::{
+                invoke,
+                invoke_signed
+            },
+            program_error::ProgramError,
+            program_pack::Pack,
+            program_utils::limited_deserialize,
+            pubkey::Pubkey,
+            system_instruction::{
+                allocate,
+                assign,
+                transfer
+            },
+            system_program,
+            sysvar::{
+                rent::Rent,
+                Sysvar
+            }
+        },
+        spl_token_2022::{
+            extension::{
+                transfer_fee::{
+                    instruction::transfer_checked_with_fee,
+                    TransferFeeConfig
+                },
+                transfer_hook,
+                BaseStateWithExtensions,
+                StateWithExtensions
+            },
+            instruction::{
+                close_account,
+                transfer_checked
+            },
+            state::{
+                Account as TokenAccount,
+                Mint,
+                Multisig
+            }
+        },
+        spl_associated_token_account::{
+            get_associated_token_address_with_program_id,
+            instruction::create_associated_token_account_idempotent
+        },
+        spl_memo::build_memo,
+        spl_transfer_hook_interface::{
+            get_extra_account_metas_address,
+            onchain::add_cpi_accounts_for_execute
+        },
+        crate::{
+            error::SplitterError,
+            state::{
+                get_config_address,
+                get_policy_address,
+                get_program_data_address,
+                Config,
+                PlanAllocation,
+                SplitPlan,
+                MAX_PLAN_RECIPIENTS,
+                Policy,
+                PolicyKind,
+                SplitPolicy,
+                MAX_FEE_BASIS_POINTS
+            },
+            event::{
+                SplitExecuted,
+                SplitterEvent,
+                TransferLeg
+            },
+            instruction::{
+                AssetTotal,
+                BalanceGuard,
+                RentExemptionPolicy,
+                SplitKind,
+                SplitMemo,
+                SplitSummary,
+                TransferRoute
+            }
+        },
+        super::ExecutedTransfer
+    };
+
+    /// Sums the requested amounts, failing instead of wrapping on overflow.
+    pub fn checked_sum(amounts: &[u64]) -> Result<u64, ProgramError> {
+        amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(SplitterError::AmountOverflow.into())
+    }
+
+    /// Splits `total` proportionally to `shares`.
+    ///
+    /// Every recipient gets `floor(total * share / sum(shares))`, then the units lost to
+    /// rounding are given one each to the recipients with the largest truncated
+    /// remainder, ties going to the lower index. The result always sums to `total`.
+    pub fn allocate_by_shares(total: u64, shares: &[u64]) -> Result<Vec<u64>, ProgramError> {
+        let total_shares = shares
+            .iter()
+            .try_fold(0u128, |sum, share| sum.checked_add(*share as u128))
+            .ok_or(SplitterError::AmountOverflow)?;
+        if total_shares == 0 {
+            return Err(
+                SplitterError::InvalidShares.into()
+            );
+        };
+
+        let mut allocations: Vec<u64> = Vec::with_capacity(shares.len());
+        let mut remainders: Vec<(u128, usize)> = Vec::with_capacity(shares.len());
+        for (index, share) in shares.iter().enumerate() {
+            let scaled = (total as u128)
+                .checked_mul(*share as u128)
+                .ok_or(SplitterError::AmountOverflow)?;
+
+            allocations.push((scaled / total_shares) as u64);
+            remainders.push((scaled % total_shares, index));
+        };
+
+        let allocated = checked_sum(&allocations)?;
+        let leftover = total
+            .checked_sub(allocated)
+            .ok_or(SplitterError::AmountOverflow)? as usize;
+
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, index) in remainders.iter().take(leftover) {
+            allocations[*index] += 1;
+        };
+
+        Ok(allocations)
+    }
+
+    /// Returns the amount to send to a lamport destination under the rent-exemption
+    /// policy, `None` when the destination is skipped.
+    pub fn apply_rent_exemption_policy(
+        destination_account: &AccountInfo,
+        amount: u64,
+        policy: &RentExemptionPolicy,
+        rent: &Rent
+    ) -> Result<Option<u64>, ProgramError> {
+        let minimum_balance = rent.minimum_balance(destination_account.data_len());
+        let balance = destination_account
+            .lamports()
+            .checked_add(amount)
+            .ok_or(SplitterError::AmountOverflow)?;
+        if balance >= minimum_balance {
+            return Ok(Some(amount));
+        };
+
+        match policy {
+            RentExemptionPolicy::Fail => {
+                msg!(
+                    "Destination {} would hold {} lamports but needs {} to be rent-exempt",
+                    destination_account.key,
+                    balance,
+                    minimum_balance
+                );
+
+                Err(
+                    SplitterError::DestinationNotRentExempt.into()
+                )
+            },
+            RentExemptionPolicy::Skip => Ok(None),
+            RentExemptionPolicy::TopUp => Ok(
+                Some(minimum_balance - destination_account.lamports())
+            )
+        }
+    }
+
+    /// Unpacks a token account of either token program, extensions included, and rejects
+    /// it if it is frozen or belongs to another mint.
+    pub fn unpack_token_account(
+        account_info: &AccountInfo,
+        mint: &Pubkey,
+        token_program_id: &Pubkey
+    ) -> Result<TokenAccount, ProgramError> {
+        if account_info.owner != token_program_id {
+            return Err(
+                SplitterError::InvalidTokenAccount.into()
+            );
+        };
+
+        let token_account = StateWithExtensions::<TokenAccount>::unpack(&account_info.try_borrow_data()?)
+            .map_err(|_| SplitterError::InvalidTokenAccount)?
+            .base;
+
+        if token_account.mint != *mint {
+            return Err(
+                SplitterError::MintMismatch.into()
+            );
+        };
+
+        if token_account.is_frozen() {
+            return Err(
+                SplitterError::TokenAccountFrozen.into()
+            );
+        };
+
+        Ok(token_account)
+    }
+
+    /// Ensures the mint is owned by the token program and has the expected decimals.
+    pub fn validate_mint(
+        mint_info: &AccountInfo,
+        token_program_id: &Pubkey,
+        decimals: u8
+    ) -> ProgramResult {
+        if mint_info.owner != token_program_id {
+            return Err(
+                SplitterError::InvalidMint.into()
+            );
+        };
+
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_info.try_borrow_data()?)
+            .map_err(|_| SplitterError::InvalidMint)?
+            .base;
+        if mint.decimals != decimals {
+            msg!(
+                "Mint {} has {} decimals but {} were expected",
+                mint_info.key,
+                mint.decimals,
+                decimals
+            );
+
+            return Err(
+                SplitterError::MintDecimalsMismatch.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Returns the destination accounts paired with the amounts, trailing accounts are ignored.
+    pub fn take_destinations<'a, 'b>(
+        accounts: &'a [AccountInfo<'b>],
+        amounts: &[u64]
+    ) -> Result<&'a [AccountInfo<'b>], ProgramError> {
+        accounts
+            .get(..amounts.len())
+            .ok_or_else(|| {
+                msg!(
+                    "Expected {} destination accounts but {} were provided",
+                    amounts.len(),
+                    accounts.len()
+                );
+
+                SplitterError::AccountsAmountsMismatch.into()
+            })
+    }
+
+    pub fn validate_program_id(
+        program_info: &AccountInfo,
+        expected_program_id: &Pubkey,
+        error: SplitterError
+    ) -> ProgramResult {
+        if program_info.key != expected_program_id {
+            return Err(error.into());
+        };
+
+        Ok(())
+    }
+
+    /// Returns the native mint of the given token program.
+    pub fn native_mint_id(token_program_id: &Pubkey) -> Pubkey {
+        if *token_program_id == spl_token_2022::ID {
+            spl_token_2022::native_mint::ID
+        } else {
+            spl_token::native_mint::ID
+        }
+    }
+
+    /// Accepts both the spl-token and the token-2022 program.
+    pub fn validate_token_program(program_info: &AccountInfo) -> ProgramResult {
+        if *program_info.key != spl_token::ID && *program_info.key != spl_token_2022::ID {
+            return Err(
+                SplitterError::InvalidTokenProgram.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    pub fn validate_signer(account_info: &AccountInfo) -> ProgramResult {
+        if !account_info.is_signer {
+            return Err(
+                SplitterError::MissingRequiredSignature.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Ensures the operator signed, or when it is an SPL multisig of the token program
+    /// that at least `m` of its signers signed, and returns those signer accounts.
+    pub fn validate_operator<'a>(
+        operator: &AccountInfo<'a>,
+        token_program_id: &Pubkey,
+        accounts: &[AccountInfo<'a>]
+    ) -> Result<Vec<AccountInfo<'a>>, ProgramError> {
+        if operator.owner != token_program_id || operator.data_len() != Multisig::LEN {
+            validate_signer(operator)?;
+
+            return Ok(vec![]);
+        };
+
+        let multisig = Multisig::unpack(&operator.try_borrow_data()?)
+            .map_err(|_| SplitterError::InvalidMultisig)?;
+        let signers = multisig.signers[..multisig.n as usize]
+            .iter()
+            .filter_map(|signer| {
+                accounts
+                    .iter()
+                    .find(|account| account.key == signer && account.is_signer)
+                    .cloned()
+            })
+            .collect::<Vec<AccountInfo>>();
+        if signers.len() < multisig.m as usize {
+            msg!(
+                "Multisig operator requires {} signers but {} signed",
+                multisig.m,
+                signers.len()
+            );
+
+            return Err(
+                SplitterError::MissingRequiredSignature.into()
+            );
+        };
+
+        Ok(signers)
+    }
+
+    pub fn validate_writable(account_info: &AccountInfo) -> ProgramResult {
+        if !account_info.is_writable {
+            return Err(
+                SplitterError::AccountNotWritable.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Ensures every destination is writable, differs from all sources and appears only once.
+    pub fn validate_destinations(
+        sources: &[&AccountInfo],
+        destinations: &[AccountInfo]
+    ) -> ProgramResult {
+        for (index, destination) in destinations.iter().enumerate() {
+            validate_writable(destination)?;
+
+            if sources.iter().any(|source| source.key == destination.key) {
+                return Err(
+                    SplitterError::DestinationIsSource.into()
+                );
+            };
+
+            if destinations[..index].iter().any(|previous| previous.key == destination.key) {
+                return Err(
+                    SplitterError::DuplicateDestination.into()
+                );
+            };
+        };
+
+        Ok(())
+    }
+
+    /// Grosses a net amount up by the mint's transfer fee for `epoch`.
+    ///
+    /// Returns the amount to transfer and the fee to assert on the transfer, mints
+    /// without a `TransferFeeConfig` are transferred as is without a fee.
+    pub fn gross_up_transfer_amount(
+        mint_info: &AccountInfo,
+        net_amount: u64,
+        epoch: u64
+    ) -> Result<(u64, Option<u64>), ProgramError> {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+            .map_err(|_| SplitterError::InvalidMint)?;
+
+        let transfer_fee_config = match mint.get_extension::<TransferFeeConfig>() {
+            Ok(transfer_fee_config) => transfer_fee_config,
+            Err(_) => return Ok((net_amount, None))
+        };
+
+        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+        let gross_amount = transfer_fee
+            .calculate_pre_fee_amount(net_amount)
+            .ok_or(SplitterError::TransferFeeCalculationFailed)?;
+        let fee = transfer_fee
+            .calculate_fee(gross_amount)
+            .ok_or(SplitterError::TransferFeeCalculationFailed)?;
+
+        if gross_amount.checked_sub(fee) != Some(net_amount) {
+            msg!(
+                "Net amount {} can not be reached through the transfer fee of mint {}",
+                net_amount,
+                mint_info.key
+            );
+
+            return Err(
+                SplitterError::TransferFeeCalculationFailed.into()
+            );
+        };
+
+        Ok((gross_amount, Some(fee)))
+    }
+
+    /// Returns the mint's transfer hook program, if any, after making sure the hook
+    /// program and its extra-account-metas account were provided.
+    pub fn validate_transfer_hook_accounts(
+        mint_info: &AccountInfo,
+        additional_accounts: &[AccountInfo]
+    ) -> Result<Option<Pubkey>, ProgramError> {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+            .map_err(|_| SplitterError::InvalidMint)?;
+
+        let hook_program_id = match transfer_hook::get_program_id(&mint) {
+            Some(hook_program_id) => hook_program_id,
+            None => return Ok(None)
+        };
+
+        let validation_address = get_extra_account_metas_address(mint_info.key, &hook_program_id);
+        for required_account in [hook_program_id, validation_address].iter() {
+            if !additional_accounts.iter().any(|account| account.key == required_account) {
+                msg!(
+                    "Transfer hook account {} of mint {} is missing",
+                    required_account,
+                    mint_info.key
+                );
+
+                return Err(
+                    SplitterError::MissingTransferHookAccounts.into()
+                );
+            };
+        };
+
+        Ok(Some(hook_program_id))
+    }
+
+    /// Transfers tokens with `transfer_checked`, or `transfer_checked_with_fee` when a fee
+    /// is expected, forwarding the signers of a multisig authority and the extra accounts
+    /// of the mint's transfer hook if it has one.
+    pub fn invoke_token_transfer<'a>(
+        token_program: &AccountInfo<'a>,
+        source: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        authority: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>],
+        additional_accounts: &[AccountInfo<'a>],
+        amount: u64,
+        decimals: u8,
+        fee: Option<u64>
+    ) -> ProgramResult {
+        let signer_pubkeys = signers
+            .iter()
+            .map(|signer| signer.key)
+            .collect::<Vec<&Pubkey>>();
+        let mut cpi_instruction = match fee {
+            Some(fee) => transfer_checked_with_fee(
+                token_program.key,
+                source.key,
+                mint.key,
+                destination.key,
+                authority.key,
+                &signer_pubkeys,
+                amount,
+                decimals,
+                fee
+            )?,
+            None => transfer_checked(
+                token_program.key,
+                source.key,
+                mint.key,
+                destination.key,
+                authority.key,
+                &signer_pubkeys,
+                amount,
+                decimals
+            )?
+        };
+        let mut cpi_account_infos = vec![
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone()
+        ];
+        cpi_account_infos.extend_from_slice(signers);
+
+        if let Some(hook_program_id) = validate_transfer_hook_accounts(mint, additional_accounts)? {
+            add_cpi_accounts_for_execute(
+                &mut cpi_instruction,
+                &mut cpi_account_infos,
+                mint.key,
+                &hook_program_id,
+                additional_accounts
+            )?;
+        };
+
+        invoke(
+            &cpi_instruction,
+            &cpi_account_infos
+        )
+    }
+
+    /// Creates the associated token account of every recipient wallet idempotently, then
+    /// returns the accounts laid out for a single-mint split paying those token accounts.
+    ///
+    /// The rent payer, programs and wallets are kept after the destinations so transfer
+    /// hooks can still require them.
+    pub fn create_recipient_token_accounts<'a>(
+        accounts: &[AccountInfo<'a>],
+        recipients_len: usize,
+        decimals: u8,
+        policy: &SplitPolicy
+    ) -> Result<Vec<AccountInfo<'a>>, ProgramError> {
+        let accounts_info = &mut accounts.iter();
+
+        let operator = next_account_info(accounts_info)?;
+        let token_program = next_account_info(accounts_info)?;
+        let mint = next_account_info(accounts_info)?;
+        let source_ata = next_account_info(accounts_info)?;
+        let rent_payer = next_account_info(accounts_info)?;
+        let system_program_info = next_account_info(accounts_info)?;
+        let associated_token_program = next_account_info(accounts_info)?;
+        let remaining_accounts = accounts_info.as_slice();
+
+        let recipients_accounts_len = recipients_len
+            .checked_mul(2usize)
+            .ok_or(SplitterError::AccountsAmountsMismatch)?;
+        if remaining_accounts.len() < recipients_accounts_len {
+            msg!(
+                "Expected {} wallet and destination accounts but {} were provided",
+                recipients_accounts_len,
+                remaining_accounts.len()
+            );
+
+            return Err(
+                SplitterError::AccountsAmountsMismatch.into()
+            );
+        };
+        let (wallets, remaining_accounts) = remaining_accounts.split_at(recipients_len);
+        let (destination_atas, additional_accounts) = remaining_accounts.split_at(recipients_len);
+
+        // the operator and mint are checked before any token account is created
+        validate_token_program(token_program)?;
+        validate_operator(operator, token_program.key, accounts)?;
+        validate_mint(mint, token_program.key, decimals)?;
+        validate_program_id(system_program_info, &system_program::ID, SplitterError::InvalidSystemProgram)?;
+        validate_program_id(
+            associated_token_program,
+            &spl_associated_token_account::ID,
+            SplitterError::InvalidAssociatedTokenProgram
+        )?;
+        validate_signer(rent_payer)?;
+        validate_writable(rent_payer)?;
+        policy.validate_mint(mint.key)?;
+        for wallet in wallets.iter() {
+            policy.validate_recipient(wallet.key)?;
+        };
+
+        for (wallet, destination_ata) in wallets.iter().zip(destination_atas.iter()) {
+            let associated_token_address = get_associated_token_address_with_program_id(
+                wallet.key,
+                mint.key,
+                token_program.key
+            );
+            if destination_ata.key != &associated_token_address {
+                msg!(
+                    "Destination {} is not the associated token account of wallet {}",
+                    destination_ata.key,
+                    wallet.key
+                );
+
+                return Err(
+                    SplitterError::InvalidAssociatedTokenAccount.into()
+                );
+            };
+
+            invoke(
+                &create_associated_token_account_idempotent(
+                    rent_payer.key,
+                    wallet.key,
+                    mint.key,
+                    token_program.key
+                ),
+                &[
+                    rent_payer.clone(),
+                    destination_ata.clone(),
+                    wallet.clone(),
+                    mint.clone(),
+                    system_program_info.clone(),
+                    token_program.clone(),
+                    associated_token_program.clone()
+                ]
+            )?;
+        };
+
+        Ok(
+            [operator, token_program, mint, source_ata]
+                .into_iter()
+                .chain(destination_atas.iter())
+                .chain(additional_accounts.iter())
+                .chain([rent_payer, system_program_info, associated_token_program].into_iter())
+                .chain(wallets.iter())
+                .cloned()
+                .collect()
+        )
+    }
+
+    /// Closes a token account with `close_account`, sending its lamports to `destination`.
+    pub fn invoke_close_account<'a>(
+        token_program: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        authority: &AccountInfo<'a>,
+        signers: &[AccountInfo<'a>]
+    ) -> ProgramResult {
+        let signer_pubkeys = signers
+            .iter()
+            .map(|signer| signer.key)
+            .collect::<Vec<&Pubkey>>();
+        let mut cpi_account_infos = vec![
+            account.clone(),
+            destination.clone(),
+            authority.clone()
+        ];
+        cpi_account_infos.extend_from_slice(signers);
+
+        invoke(
+            &close_account(
+                token_program.key,
+                account.key,
+                destination.key,
+                authority.key,
+                &signer_pubkeys
+            )?,
+            &cpi_account_infos
+        )
+    }
+
+    /// Reads the config of a split, the config PDA must be among the accounts and is the
+    /// default config, without fee, as long as it is not initialized.
+    pub fn resolve_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo]
+    ) -> Result<Config, ProgramError> {
+        let config_address = get_config_address(program_id).0;
+        let config_info = accounts
+            .iter()
+            .find(|account| *account.key == config_address)
+            .ok_or(SplitterError::MissingConfig)?;
+
+        if config_info.owner != program_id {
+            if !config_info.data_is_empty() {
+                return Err(
+                    SplitterError::InvalidConfig.into()
+                );
+            };
+
+            return Ok(Config::default());
+        };
+
+        Config::unpack(&config_info.try_borrow_data()?)
+    }
+
+    /// Applies `update` to the initialized config on behalf of its admin.
+    pub fn update_config<F: FnOnce(&mut Config) -> ProgramResult>(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        update: F
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let admin = next_account_info(accounts_info)?;
+        let config_info = next_account_info(accounts_info)?;
+
+        validate_writable(config_info)?;
+        let mut config = authorize_admin(program_id, admin, config_info)?;
+
+        update(&mut config)?;
+        config_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&config.pack()?);
+
+        Ok(())
+    }
+
+    /// Loads the initialized config and checks that `admin` is its signing admin.
+    pub fn authorize_admin(
+        program_id: &Pubkey,
+        admin: &AccountInfo,
+        config_info: &AccountInfo
+    ) -> Result<Config, ProgramError> {
+        validate_signer(admin)?;
+        if *config_info.key != get_config_address(program_id).0 || config_info.owner != program_id {
+            return Err(
+                SplitterError::InvalidConfig.into()
+            );
+        };
+
+        let config = Config::unpack(&config_info.try_borrow_data()?)?;
+        if config.admin != *admin.key {
+            return Err(
+                SplitterError::InvalidAdmin.into()
+            );
+        };
+
+        Ok(config)
+    }
+
+    /// Finds the policy PDA of `kind`, `None` when its account is not initialized.
+    pub fn resolve_policy<'a>(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo<'a>],
+        kind: PolicyKind
+    ) -> Result<Option<AccountInfo<'a>>, ProgramError> {
+        let policy_address = get_policy_address(program_id, kind).0;
+        let policy_info = accounts
+            .iter()
+            .find(|account| *account.key == policy_address)
+            .ok_or(SplitterError::MissingPolicy)?;
+
+        if policy_info.owner != program_id {
+            if !policy_info.data_is_empty() {
+                return Err(
+                    SplitterError::InvalidPolicy.into()
+                );
+            };
+
+            return Ok(None);
+        };
+
+        // only the header is read, the addresses are searched in place by the split
+        let (policy_kind, _) = Policy::unpack_header(&policy_info.try_borrow_data()?)?;
+        if policy_kind != kind {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        Ok(Some(policy_info.clone()))
+    }
+
+    /// Checks that `policy_info` is the writable, initialized policy PDA of `kind`.
+    pub fn validate_policy_account(
+        program_id: &Pubkey,
+        policy_info: &AccountInfo,
+        kind: PolicyKind
+    ) -> ProgramResult {
+        validate_writable(policy_info)?;
+        if *policy_info.key != get_policy_address(program_id, kind).0 || policy_info.owner != program_id {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Checks that `upgrade_authority` signs and is the upgrade authority recorded in the
+    /// program data account of the program.
+    pub fn authorize_upgrade_authority(
+        program_id: &Pubkey,
+        upgrade_authority: &AccountInfo,
+        program_data_info: &AccountInfo
+    ) -> ProgramResult {
+        validate_signer(upgrade_authority)?;
+        if *program_data_info.key != get_program_data_address(program_id)
+            || *program_data_info.owner != bpf_loader_upgradeable::ID
+        {
+            return Err(
+                SplitterError::InvalidProgramData.into()
+            );
+        };
+
+        let upgrade_authority_address = match limited_deserialize(
+            &program_data_info.try_borrow_data()?,
+            UpgradeableLoaderState::size_of_programdata_metadata() as u64
+        ) {
+            Ok(UpgradeableLoaderState::ProgramData { upgrade_authority_address, .. }) => upgrade_authority_address,
+            _ => return Err(
+                SplitterError::InvalidProgramData.into()
+            )
+        };
+        if upgrade_authority_address != Some(*upgrade_authority.key) {
+            return Err(
+                SplitterError::InvalidUpgradeAuthority.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Creates the program account at the PDA signed by `seeds`, rent-exempt for `space`.
+    ///
+    /// Lamports already held by the address are kept and only the rest is paid, so sending
+    /// lamports to a PDA beforehand can not block its creation.
+    pub fn create_program_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        space: usize,
+        seeds: &[&[u8]]
+    ) -> ProgramResult {
+        top_up_rent(payer, account, system_program_info, space)?;
+
+        invoke_signed(
+            &allocate(account.key, space as u64),
+            &[
+                account.clone(),
+                system_program_info.clone()
+            ],
+            &[seeds]
+        )?;
+        invoke_signed(
+            &assign(account.key, program_id),
+            &[
+                account.clone(),
+                system_program_info.clone()
+            ],
+            &[seeds]
+        )
+    }
+
+    /// Transfers from `payer` what `account` misses to be rent-exempt for `space`.
+    pub fn top_up_rent<'a>(
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        space: usize
+    ) -> ProgramResult {
+        let required_lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if required_lamports > 0 {
+            invoke(
+                &transfer(payer.key, account.key, required_lamports),
+                &[
+                    payer.clone(),
+                    account.clone(),
+                    system_program_info.clone()
+                ]
+            )?;
+        };
+
+        Ok(())
+    }
+
+    /// Drains the lamports of a program account to `receiver` and hands the emptied
+    /// account back to the system program.
+    pub fn close_program_account(
+        account: &AccountInfo,
+        receiver: &AccountInfo
+    ) -> ProgramResult {
+        let lamports = account.lamports();
+        **account.try_borrow_mut_lamports()? = 0;
+        **receiver.try_borrow_mut_lamports()? = receiver
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(SplitterError::AmountOverflow)?;
+        account.realloc(0, false)?;
+        account.assign(&system_program::ID);
+
+        Ok(())
+    }
+
+    /// Loads the split plan held by `plan_info`.
+    pub fn load_plan(
+        program_id: &Pubkey,
+        plan_info: &AccountInfo
+    ) -> Result<SplitPlan, ProgramError> {
+        if plan_info.owner != program_id {
+            return Err(
+                SplitterError::InvalidPlan.into()
+            );
+        };
+
+        SplitPlan::unpack(&plan_info.try_borrow_data()?)
+    }
+
+    /// Loads the split plan and checks that `authority` is its signing authority.
+    pub fn authorize_plan_authority(
+        program_id: &Pubkey,
+        authority: &AccountInfo,
+        plan_info: &AccountInfo
+    ) -> Result<SplitPlan, ProgramError> {
+        validate_signer(authority)?;
+
+        let plan = load_plan(program_id, plan_info)?;
+        if plan.authority != *authority.key {
+            return Err(
+                SplitterError::InvalidPlanAuthority.into()
+            );
+        };
+
+        Ok(plan)
+    }
+
+    /// Applies `update` to the unlocked split plan on behalf of its authority and bumps
+    /// its version.
+    pub fn update_plan<F: FnOnce(&mut SplitPlan) -> ProgramResult>(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        update: F
+    ) -> ProgramResult {
+        let accounts_info = &mut accounts.iter();
+
+        let authority = next_account_info(accounts_info)?;
+        let plan_info = next_account_info(accounts_info)?;
+
+        validate_writable(plan_info)?;
+        let mut plan = authorize_plan_authority(program_id, authority, plan_info)?;
+        if plan.locked {
+            return Err(
+                SplitterError::PlanLocked.into()
+            );
+        };
+
+        update(&mut plan)?;
+        plan.version = plan
+            .version
+            .checked_add(1u64)
+            .ok_or(SplitterError::AmountOverflow)?;
+        plan_info
+            .try_borrow_mut_data()?
+            .copy_from_slice(&plan.pack()?);
+
+        Ok(())
+    }
+
+    pub fn validate_plan_allocation(
+        recipients: &[Pubkey],
+        allocation: &PlanAllocation
+    ) -> ProgramResult {
+        if recipients.is_empty() || recipients.len() > MAX_PLAN_RECIPIENTS {
+            return Err(
+                SplitterError::InvalidPlanRecipients.into()
+            );
+        };
+        if recipients.len() != allocation.len() {
+            return Err(
+                SplitterError::AccountsAmountsMismatch.into()
+            );
+        };
+        if let PlanAllocation::Shares(shares) = allocation {
+            if checked_sum(shares)? == 0 {
+                return Err(
+                    SplitterError::InvalidShares.into()
+                );
+            };
+        };
+
+        Ok(())
+    }
+
+    /// Checks that the destinations are the recipients of the plan, in order.
+    pub fn validate_plan_destinations(
+        plan: &SplitPlan,
+        destination_accounts: &[AccountInfo]
+    ) -> ProgramResult {
+        let matches = destination_accounts.len() >= plan.recipients.len()
+            && plan
+                .recipients
+                .iter()
+                .zip(destination_accounts.iter())
+                .all(|(recipient, destination_account)| destination_account.key == recipient);
+        if !matches {
+            return Err(
+                SplitterError::PlanDestinationMismatch.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Checks that `config_info` is the writable, initialized config PDA.
+    pub fn validate_config_account(
+        program_id: &Pubkey,
+        config_info: &AccountInfo
+    ) -> ProgramResult {
+        validate_writable(config_info)?;
+        if *config_info.key != get_config_address(program_id).0 || config_info.owner != program_id {
+            return Err(
+                SplitterError::InvalidConfig.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Position of the queued change `id` in the config.
+    pub fn find_pending_change(config: &Config, id: u64) -> Result<usize, ProgramError> {
+        config
+            .pending_changes
+            .iter()
+            .position(|pending_change| pending_change.id == id)
+            .ok_or_else(|| SplitterError::PendingChangeNotFound.into())
+    }
+
+    pub fn validate_fee_basis_points(fee_basis_points: u16) -> ProgramResult {
+        if fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(
+                SplitterError::InvalidFee.into()
+            );
+        };
+
+        Ok(())
+    }
+
+    /// Finds the treasury account, or its token account, that receives the protocol fee.
+    pub fn resolve_treasury_account<'a, 'b>(
+        accounts: &'a [AccountInfo<'b>],
+        treasury: &Pubkey
+    ) -> Result<&'a AccountInfo<'b>, ProgramError> {
+        accounts
+            .iter()
+            .find(|account| account.key == treasury)
+            .ok_or_else(|| SplitterError::MissingTreasuryAccount.into())
+    }
+
+    /// Finds the memo program account when the split has a memo, per-leg memos must match
+    /// the `legs` of the split.
+    pub fn resolve_memo_program<'a, 'b>(
+        accounts: &'a [AccountInfo<'b>],
+        memo: &Option<SplitMemo>,
+        legs: usize
+    ) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+        match memo {
+            None => return Ok(None),
+            Some(SplitMemo::PerLeg(memos)) if memos.len() != legs => {
+                return Err(
+                    SplitterError::MemoLegsMismatch.into()
+                );
+            },
+            _ => ()
+        };
+
+        accounts
+            .iter()
+            .find(|account| account.key == &spl_memo::ID)
+            .map(Some)
+            .ok_or_else(|| SplitterError::MissingMemoProgram.into())
+    }
+
+    /// Logs the memo of a leg through the memo program, it must run right before the
+    /// transfer of the leg for Token-2022 to accept it as the transfer's memo.
+    pub fn invoke_memo(
+        memo_program: Option<&AccountInfo>,
+        memo: &Option<SplitMemo>,
+        leg_index: usize
+    ) -> ProgramResult {
+        let (memo_program, memo) = match (memo_program, memo) {
+            (Some(memo_program), Some(SplitMemo::Batch(memo))) => (memo_program, memo),
+            (Some(memo_program), Some(SplitMemo::PerLeg(memos))) => (
+                memo_program,
+                memos
+                    .get(leg_index)
+                    .ok_or(SplitterError::MemoLegsMismatch)?
+            ),
+            _ => return Ok(())
+        };
+        if memo.is_empty() {
+            return Ok(());
+        };
+
+        invoke(
+            &build_memo(memo.as_bytes(), &[]),
+            &[memo_program.clone()]
+        )
+    }
+
+    /// Resolves the source, destination and mint accounts a route points at.
+    pub fn resolve_route<'a, 'b>(
+        route_accounts: &'a [AccountInfo<'b>],
+        route: &TransferRoute
+    ) -> Result<(&'a AccountInfo<'b>, &'a AccountInfo<'b>, &'a AccountInfo<'b>), ProgramError> {
+        match (
+            route_accounts.get(route.source_index as usize),
+            route_accounts.get(route.destination_index as usize),
+            route_accounts.get(route.mint_index as usize)
+        ) {
+            (Some(source), Some(destination), Some(mint)) => Ok((source, destination, mint)),
+            _ => {
+                msg!(
+                    "Route {} -> {} of mint {} is out of bounds for {} accounts",
+                    route.source_index,
+                    route.destination_index,
+                    route.mint_index,
+                    route_accounts.len()
+                );
+
+                Err(
+                    SplitterError::RouteIndexOutOfBounds.into()
+                )
+            }
+        }
+    }
+
+    /// Ensures every route is in bounds and writable, never pays into an account
+    /// used as a source and is not repeated.
+    pub fn validate_routes(
+        route_accounts: &[AccountInfo],
+        routes: &[TransferRoute]
+    ) -> ProgramResult {
+        for (index, route) in routes.iter().enumerate() {
+            let (source, destination, _) = resolve_route(route_accounts, route)?;
+            validate_writable(source)?;
+            validate_writable(destination)?;
+
+            for other_route in routes.iter() {
+                let (other_source, _, _) = resolve_route(route_accounts, other_route)?;
+                if other_source.key == destination.key {
+                    return Err(
+                        SplitterError::DestinationIsSource.into()
+                    );
+                };
+            };
+
+            for previous_route in routes[..index].iter() {
+                let (
+                    previous_source,
+                    previous_destination,
+                    _
+                ) = resolve_route(route_accounts, previous_route)?;
+                if previous_source.key == source.key && previous_destination.key == destination.key {
+                    return Err(
+                        SplitterError::DuplicateDestination.into()
+                    );
+                };
+            };
+        };
+
+        Ok(())
+    }
+
+    /// Returns the balance a guard applies to: the token amount of a token account,
+    /// lamports otherwise.
+    pub fn guarded_balance(
+        accounts: &[AccountInfo],
+        guard: &BalanceGuard
+    ) -> Result<u64, ProgramError> {
+        let account_info = accounts
+            .iter()
+            .find(|account_info| *account_info.key == guard.account)
+            .ok_or(SplitterError::MissingGuardAccount)?;
+
+        if *account_info.owner == spl_token::ID || *account_info.owner == spl_token_2022::ID {
+            if let Ok(token_account) = StateWithExtensions::<TokenAccount>::unpack(&account_info.try_borrow_data()?) {
+                return Ok(token_account.base.amount);
+            };
+        };
+
+        Ok(account_info.lamports())
+    }
+
+    pub fn validate_starting_balances(
+        accounts: &[AccountInfo],
+        guards: &[BalanceGuard]
+    ) -> ProgramResult {
+        for guard in guards.iter() {
+            let balance = guarded_balance(accounts, guard)?;
+            if guard.min_before.map_or(false, |min_before| balance < min_before) {
+                return Err(
+                    SplitterError::StartingBalanceBelowGuard.into()
+                );
+            };
+        };
+
+        Ok(())
+    }
+
+    pub fn validate_remaining_balances(
+        accounts: &[AccountInfo],
+        guards: &[BalanceGuard]
+    ) -> ProgramResult {
+        for guard in guards.iter() {
+            let balance = guarded_balance(accounts, guard)?;
+            if guard.min_after.map_or(false, |min_after| balance < min_after) {
+                return Err(
+                    SplitterError::RemainingBalanceBelowGuard.into()
+                );
+            };
+        };
+
+        Ok(())
+    }
+
+    /// Builds the summary a split returns from the transfers it made.
+    pub fn summarize_transfers(
+        kind: SplitKind,
+        executed_transfers: &[ExecutedTransfer]
+    ) -> Result<SplitSummary, ProgramError> {
+        let mut totals: Vec<AssetTotal> = vec![];
+        let mut fees: Vec<AssetTotal> = vec![];
+        let mut destinations: Vec<&[u8]> = Vec::with_capacity(executed_transfers.len());
+        for executed_transfer in executed_transfers.iter() {
+            // the protocol fee is reported on its own so the legs are those the caller requested
+            let asset_totals = if executed_transfer.protocol_fee {
+                &mut fees
+            } else {
+                destinations.push(executed_transfer.destination.as_ref());
+
+                &mut totals
+            };
+
+            match asset_totals.iter_mut().find(|total| total.mint == executed_transfer.mint) {
+                Some(total) => {
+                    total.amount = total.amount
+                        .checked_add(executed_transfer.amount)
+                        .ok_or(SplitterError::AmountOverflow)?;
+                },
+                None => asset_totals.push(
+                    AssetTotal {
+                        mint: executed_transfer.mint,
+                        amount: executed_transfer.amount
+                    }
+                )
+            };
+        };
+
+        Ok(
+            SplitSummary {
+                kind,
+                legs: destinations.len() as u32,
+                totals,
+                recipients_hash: hashv(&destinations).to_bytes(),
+                fees
+            }
+        )
+    }
+
+    /// Emits the `SplitExecuted` header of a split followed by a `TransferLeg` per transfer,
+    /// the protocol fee transfers excepted.
+    pub fn emit_split_events(
+        summary: &SplitSummary,
+        executed_transfers: &[ExecutedTransfer]
+    ) -> ProgramResult {
+        SplitterEvent::SplitExecuted(
+            SplitExecuted {
+                kind: summary.kind,
+                legs: summary.legs,
+                totals: summary.totals.clone(),
+                recipients_hash: summary.recipients_hash,
+                fees: summary.fees.clone()
+            }
+        ).emit()?;
+
+        let legs = executed_transfers
+            .iter()
+            .filter(|executed_transfer| !executed_transfer.protocol_fee);
+        for (index, executed_transfer) in legs.enumerate() {
+            SplitterEvent::TransferLeg(
+                TransferLeg {
+                    index: index as u32,
+                    source: executed_transfer.source,
+                    destination: executed_transfer.destination,
+                    mint: executed_transfer.mint,
+                    amount: executed_transfer.amount
+                }
+            ).emit()?;
+        };
+
+        Ok(())
+    }
 }
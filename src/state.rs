@@ -1 +1,431 @@
-//
\ No newline at end of file
+use {
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        bpf_loader_upgradeable,
+        msg,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_instruction::MAX_PERMITTED_DATA_LENGTH
+    },
+    std::cmp::Ordering,
+    crate::{
+        error::SplitterError,
+        instruction::SplitterInstruction
+    }
+};
+
+/// Seed of the config PDA
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Basis points of the whole amount
+pub const BASIS_POINTS: u16 = 10_000;
+
+/// Upper bound of the protocol fee, a tenth of the amount
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+/// Number of config changes that can wait for the timelock at the same time
+pub const MAX_PENDING_CHANGES: usize = 4;
+
+/// Config update that only takes effect once its timelock has elapsed
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum ConfigChange {
+    /// Replaces the protocol fee, in basis points of every transfer, at most
+    /// `MAX_FEE_BASIS_POINTS`
+    Fee(u16),
+    /// Replaces the treasury receiving the protocol fee
+    Treasury(Pubkey),
+    /// Replaces the delay, in seconds, of the changes queued afterwards
+    TimelockDelay(u32)
+}
+
+/// Config change queued by the admin
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PendingChange {
+    /// Identifier to execute or cancel the change with
+    pub id: u64,
+    pub change: ConfigChange,
+    /// Unix timestamp from which the change can be executed
+    pub executable_at: i64
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8 + (1 + 32) + 8;
+}
+
+/// Program-wide configuration, stored in the PDA derived from `CONFIG_SEED`
+///
+/// The account starts with the 8-byte discriminator of `account:config` followed by
+/// the borsh-serialized config, zero-padded to `Config::LEN`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Signer of the config updates
+    pub admin: Pubkey,
+    /// Part of every split transfer that is sent to the treasury instead
+    pub fee_basis_points: u16,
+    /// Receives the fees, lamports directly and tokens in its associated token account
+    /// of the mint
+    pub treasury: Pubkey,
+    /// Splits fail with `SplitsPaused` while set
+    pub paused: bool,
+    /// Bump seed of the config PDA
+    pub bump: u8,
+    /// Admin proposed by the current one, becomes the admin once it accepts
+    pub pending_admin: Option<Pubkey>,
+    /// Seconds a queued change waits before it can be executed
+    pub timelock_delay: u32,
+    /// Identifier of the next queued change
+    pub next_change_id: u64,
+    /// Changes waiting for their timelock, at most `MAX_PENDING_CHANGES`
+    pub pending_changes: Vec<PendingChange>
+}
+
+impl Config {
+    pub const LEN: usize = 8 + 32 + 2 + 32 + 1 + 1 + (1 + 32) + 4 + 8
+        + (4 + MAX_PENDING_CHANGES * PendingChange::LEN);
+
+    pub fn unpack(account_data: &[u8]) -> Result<Self, ProgramError> {
+        if account_data.len() < 8
+            || account_data[..8] != SplitterInstruction::get_discriminator("account:config")
+        {
+            return Err(
+                SplitterError::InvalidConfig.into()
+            );
+        };
+
+        Self::deserialize(&mut &account_data[8..]).map_err(|_| SplitterError::InvalidConfig.into())
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut buf = Vec::with_capacity(Self::LEN);
+        buf.extend_from_slice(&SplitterInstruction::get_discriminator("account:config"));
+        buf.extend_from_slice(
+            &self.try_to_vec().map_err(|_| SplitterError::InvalidConfig)?
+        );
+        buf.resize(Self::LEN, 0);
+
+        Ok(buf)
+    }
+
+    /// Fee charged on `amount`, rounded down.
+    pub fn fee_of(&self, amount: u64) -> u64 {
+        ((amount as u128) * (self.fee_basis_points as u128) / (BASIS_POINTS as u128)) as u64
+    }
+}
+
+/// Returns the address and bump seed of the config PDA.
+pub fn get_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Returns the address of the program data account of the upgradeable `program_id`.
+pub fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// Number of addresses a policy account can hold, as many as fit in the largest account
+///
+/// The policy account grows with every listed address, by at most
+/// `MAX_PERMITTED_DATA_INCREASE` per instruction, so a long list is filled over several
+/// instructions
+pub const MAX_POLICY_ADDRESSES: usize = (MAX_PERMITTED_DATA_LENGTH as usize - Policy::HEADER_LEN) / 32;
+
+/// Restriction a policy account applies to the splits
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum PolicyKind {
+    /// Only the listed mints can be split
+    MintAllowlist,
+    /// The listed addresses can not receive any transfer, as destination or owner of the
+    /// destination token account
+    RecipientDenylist
+}
+
+impl PolicyKind {
+    /// Seed of the policy PDA of this kind
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            Self::MintAllowlist => b"mint_allowlist",
+            Self::RecipientDenylist => b"recipient_denylist"
+        }
+    }
+}
+
+/// List of addresses a split is checked against, stored in the PDA derived from the
+/// seed of its kind
+///
+/// The account starts with the 8-byte discriminator of `account:policy` followed by
+/// the borsh-serialized policy and is exactly `Policy::space` of its addresses long.
+/// Addresses are kept sorted so lookups are binary searches, made in place on the account
+/// data since a long list does not fit in the program heap
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Policy {
+    pub kind: PolicyKind,
+    /// Bump seed of the policy PDA
+    pub bump: u8,
+    /// Sorted and deduplicated, at most `MAX_POLICY_ADDRESSES`
+    pub addresses: Vec<Pubkey>
+}
+
+impl Policy {
+    /// Length of the account data before the addresses, their number included
+    pub const HEADER_LEN: usize = 8 + 1 + 1 + 4;
+
+    /// Length of the account data of a policy listing `len` addresses.
+    pub const fn space(len: usize) -> usize {
+        Self::HEADER_LEN + len * 32
+    }
+
+    pub fn unpack(account_data: &[u8]) -> Result<Self, ProgramError> {
+        if account_data.len() < 8
+            || account_data[..8] != SplitterInstruction::get_discriminator("account:policy")
+        {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        Self::deserialize(&mut &account_data[8..]).map_err(|_| SplitterError::InvalidPolicy.into())
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut buf = Vec::with_capacity(Self::space(self.addresses.len()));
+        buf.extend_from_slice(&SplitterInstruction::get_discriminator("account:policy"));
+        buf.extend_from_slice(
+            &self.try_to_vec().map_err(|_| SplitterError::InvalidPolicy)?
+        );
+
+        Ok(buf)
+    }
+
+    /// Reads the kind and the number of addresses of the policy account data, without
+    /// deserializing the addresses.
+    pub fn unpack_header(account_data: &[u8]) -> Result<(PolicyKind, usize), ProgramError> {
+        if account_data.len() < Self::HEADER_LEN
+            || account_data[..8] != SplitterInstruction::get_discriminator("account:policy")
+        {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        let kind = PolicyKind::try_from_slice(&account_data[8..9]).map_err(|_| SplitterError::InvalidPolicy)?;
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&account_data[10..Self::HEADER_LEN]);
+        let len = u32::from_le_bytes(len) as usize;
+        if account_data.len() < Self::space(len) {
+            return Err(
+                SplitterError::InvalidPolicy.into()
+            );
+        };
+
+        Ok((kind, len))
+    }
+
+    /// Binary search of `address` in the policy account data, `Ok` with its position when
+    /// listed and `Err` with the position it would be inserted at otherwise.
+    pub fn search(account_data: &[u8], address: &Pubkey) -> Result<Result<usize, usize>, ProgramError> {
+        let (_, len) = Self::unpack_header(account_data)?;
+
+        let (mut low, mut high) = (0usize, len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let offset = Self::space(middle);
+            match account_data[offset..offset + 32].cmp(address.as_ref()) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Ok(Ok(middle))
+            };
+        };
+
+        Ok(Err(low))
+    }
+
+    /// Writes the number of addresses into the policy account data.
+    pub fn pack_len(account_data: &mut [u8], len: usize) {
+        account_data[10..Self::HEADER_LEN].copy_from_slice(&(len as u32).to_le_bytes());
+    }
+}
+
+/// Policies in force for a split, the policy accounts checked in place, `None` when the
+/// policy account is not initialized
+#[derive(Clone, Debug, Default)]
+pub struct SplitPolicy<'a> {
+    pub mint_allowlist: Option<AccountInfo<'a>>,
+    pub recipient_denylist: Option<AccountInfo<'a>>
+}
+
+impl SplitPolicy<'_> {
+    /// Fails with `MintNotAllowed` when an allowlist is in force and does not list `mint`.
+    pub fn validate_mint(&self, mint: &Pubkey) -> Result<(), ProgramError> {
+        match &self.mint_allowlist {
+            Some(allowlist) if Policy::search(&allowlist.try_borrow_data()?, mint)?.is_err() => {
+                msg!("Mint {} is not allowed", mint);
+
+                Err(
+                    SplitterError::MintNotAllowed.into()
+                )
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Fails with `RecipientDenied` when the denylist lists `recipient`.
+    pub fn validate_recipient(&self, recipient: &Pubkey) -> Result<(), ProgramError> {
+        match &self.recipient_denylist {
+            Some(denylist) if Policy::search(&denylist.try_borrow_data()?, recipient)?.is_ok() => {
+                msg!("Recipient {} is denied", recipient);
+
+                Err(
+                    SplitterError::RecipientDenied.into()
+                )
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+/// Returns the address and bump seed of the policy PDA of `kind`.
+pub fn get_policy_address(program_id: &Pubkey, kind: PolicyKind) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[kind.seed()], program_id)
+}
+
+/// Seed of the split plan PDAs, followed by the authority and the plan id
+pub const PLAN_SEED: &[u8] = b"plan";
+
+/// Number of recipients a split plan can hold
+pub const MAX_PLAN_RECIPIENTS: usize = 32;
+
+/// Asset a split plan distributes
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum PlanAsset {
+    /// Lamports, split like `SplitLamports`
+    Lamports,
+    /// Tokens of the mint, split like `SplitSplTokensFromSingleMint`
+    Token {
+        mint: Pubkey,
+        /// Expected decimals of the mint
+        decimals: u8
+    }
+}
+
+/// How a split plan divides the split between its recipients, one entry per recipient
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum PlanAllocation {
+    /// Weights the total given to `ExecutePlan` is allocated by, like the splits by shares
+    Shares(Vec<u64>),
+    /// Fixed amounts paid on every execution
+    Amounts(Vec<u64>)
+}
+
+impl PlanAllocation {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Shares(shares) => shares.len(),
+            Self::Amounts(amounts) => amounts.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Reusable split, stored in the PDA derived from `PLAN_SEED`, the authority and the id
+///
+/// Every update bumps the version, a locked plan can no longer be updated nor closed. A
+/// closed plan leaves a `PlanTombstone` behind.
+/// The account starts with the 8-byte discriminator of `account:splitplan` followed by
+/// the borsh-serialized plan, zero-padded to `SplitPlan::LEN`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct SplitPlan {
+    /// Signer of the plan updates
+    pub authority: Pubkey,
+    /// Distinguishes the plans of the same authority
+    pub id: u64,
+    /// Bump seed of the plan PDA
+    pub bump: u8,
+    pub asset: PlanAsset,
+    /// Destination accounts, lamport recipients or token accounts of the mint, in split order
+    pub recipients: Vec<Pubkey>,
+    pub allocation: PlanAllocation,
+    /// Bumped on every update, `ExecutePlan` fails unless it expects the current one
+    pub version: u64,
+    /// Set once by `LockPlan`, for good
+    pub locked: bool
+}
+
+impl SplitPlan {
+    pub const LEN: usize = 8 + 32 + 8 + 1 + (1 + 32 + 1)
+        + (4 + MAX_PLAN_RECIPIENTS * 32)
+        + (1 + 4 + MAX_PLAN_RECIPIENTS * 8)
+        + 8 + 1;
+
+    pub fn unpack(account_data: &[u8]) -> Result<Self, ProgramError> {
+        if account_data.len() < 8
+            || account_data[..8] != SplitterInstruction::get_discriminator("account:splitplan")
+        {
+            return Err(
+                SplitterError::InvalidPlan.into()
+            );
+        };
+
+        Self::deserialize(&mut &account_data[8..]).map_err(|_| SplitterError::InvalidPlan.into())
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut buf = Vec::with_capacity(Self::LEN);
+        buf.extend_from_slice(&SplitterInstruction::get_discriminator("account:splitplan"));
+        buf.extend_from_slice(
+            &self.try_to_vec().map_err(|_| SplitterError::InvalidPlan)?
+        );
+        buf.resize(Self::LEN, 0);
+
+        Ok(buf)
+    }
+}
+
+/// What a closed split plan leaves at its address, the plan created again there carries on
+/// from the last version instead of restarting at 0, so an `ExecutePlan` signed for the
+/// closed plan can never run the new one
+///
+/// The account holds the 8-byte discriminator of `account:plantombstone` followed by the
+/// borsh-serialized tombstone
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PlanTombstone {
+    /// Version of the split plan when it was closed
+    pub version: u64
+}
+
+impl PlanTombstone {
+    pub const LEN: usize = 8 + 8;
+
+    pub fn unpack(account_data: &[u8]) -> Result<Self, ProgramError> {
+        if account_data.len() < 8
+            || account_data[..8] != SplitterInstruction::get_discriminator("account:plantombstone")
+        {
+            return Err(
+                SplitterError::InvalidPlan.into()
+            );
+        };
+
+        Self::deserialize(&mut &account_data[8..]).map_err(|_| SplitterError::InvalidPlan.into())
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut buf = Vec::with_capacity(Self::LEN);
+        buf.extend_from_slice(&SplitterInstruction::get_discriminator("account:plantombstone"));
+        buf.extend_from_slice(
+            &self.try_to_vec().map_err(|_| SplitterError::InvalidPlan)?
+        );
+
+        Ok(buf)
+    }
+}
+
+/// Returns the address and bump seed of the split plan PDA of `authority` with `id`.
+pub fn get_plan_address(program_id: &Pubkey, authority: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLAN_SEED, authority.as_ref(), &id.to_le_bytes()], program_id)
+}
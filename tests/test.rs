@@ -1,16 +1,90 @@
 #![allow(warnings)]
 
 use {
-    borsh::BorshSerialize,
+    base64::{
+        engine::general_purpose::STANDARD,
+        Engine
+    },
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize
+    },
     solana_asset_splitter::{
         error::SplitterError,
+        event::{
+            SplitExecuted,
+            SplitterEvent,
+            TransferLeg
+        },
         instruction::{
+            accept_admin,
+            add_policy_addresses,
+            cancel_config_change,
+            close_plan,
+            close_policy,
+            create_plan,
+            execute_lamports_plan,
+            execute_token_plan,
+            execute_config_change,
+            initialize_config,
+            initialize_policy,
+            lock_plan,
+            propose_admin,
+            queue_config_change,
+            quote_split,
+            quote_split_sweep,
+            remove_policy_addresses,
+            set_paused,
+            split_lamports,
+            split_lamports_by_shares,
+            split_mixed_assets,
+            split_spl_tokens_from_single_mint,
+            split_spl_tokens_from_single_mint_by_shares,
+            split_spl_tokens_from_single_mint_to_wallets,
+            split_lamports_to_wrapped_sol,
+            split_wrapped_sol_to_lamports,
+            treasury_account_metas,
+            update_plan,
+            AssetTotal,
+            BalanceGuard,
+            LamportSplitOptions,
+            PlanSplitOptions,
+            RentExemptionPolicy,
             SplitLamports,
+            SplitLamportsByShares,
+            SplitKind,
+            SplitLeg,
+            SplitMemo,
+            SplitQuote,
+            SplitSplTokensByRoutes,
             SplitSplTokensFromMultipleMints,
             SplitSplTokensFromSingleMint,
-            SplitterInstruction
+            SplitSplTokensFromSingleMintByShares,
+            SplitSummary,
+            SplitterInstruction,
+            TokenSplitOptions,
+            TransferRoute
         },
-        processor::Processor
+        offchain::split_spl_tokens_from_single_mint_with_extra_metas,
+        processor::Processor,
+        state::{
+            get_config_address,
+            get_plan_address,
+            get_policy_address,
+            get_program_data_address,
+            Config,
+            ConfigChange,
+            PendingChange,
+            PlanAllocation,
+            PlanAsset,
+            PlanTombstone,
+            Policy,
+            PolicyKind,
+            SplitPlan,
+            MAX_FEE_BASIS_POINTS,
+            MAX_PENDING_CHANGES,
+            MAX_PLAN_RECIPIENTS
+        }
     },
     solana_program_test::{
         processor,
@@ -19,18 +93,36 @@ use {
         ProgramTest
     },
     solana_sdk::{
-        hash::Hash,
+        account::Account,
+        account_info::AccountInfo,
+        bpf_loader_upgradeable,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        hash::{
+            hashv,
+            Hash
+        },
         instruction::{
             AccountMeta,
-            Instruction
+            Instruction,
+            InstructionError
         },
         native_token::sol_to_lamports,
         pubkey::Pubkey,
         signature::Signer,
-        signer::keypair::Keypair,
-        transaction::Transaction,
+        signer::keypair::{
+            keypair_from_seed,
+            Keypair
+        },
+        transaction::{
+            Transaction,
+            TransactionError
+        },
         system_program::ID as SYSTEM_PROGRAM_ID,
-        program_error,
+        program_error::{
+            self,
+            ProgramError
+        },
         program_pack::Pack,
         system_instruction::{
             transfer as transfer_lamports,
@@ -41,28 +133,119 @@ use {
     spl_token::{
         state::{
             Account as TokenAccount,
-            Mint as MintAccount
+            Mint as MintAccount,
+            Multisig as MultisigAccount
+        },
+        native_mint::ID as NATIVE_MINT,
+        ID as TOKEN_STANDARD_PROGRAM
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            memo_transfer::instruction::enable_required_transfer_memos,
+            transfer_fee::instruction::initialize_transfer_fee_config,
+            transfer_hook::instruction::initialize as initialize_transfer_hook,
+            ExtensionType,
+            StateWithExtensions
         },
-        ID as TOKEN_STANDARD_PROGRAM,
         instruction::{
             transfer as transfer_spl_token,
             initialize_mint as initialize_mint_account,
             initialize_account as initialize_token_account,
-            mint_to
+            initialize_immutable_owner,
+            initialize_mint_close_authority,
+            initialize_multisig,
+            mint_to,
+            reallocate
+        },
+        state::{
+            Account as TokenAccount2022,
+            Mint as MintAccount2022
+        },
+        ID as TOKEN_2022_PROGRAM
+    },
+    spl_associated_token_account::{
+        get_associated_token_address,
+        instruction::create_associated_token_account_idempotent
+    },
+    spl_tlv_account_resolution::{
+        account::ExtraAccountMeta,
+        state::ExtraAccountMetaList
+    },
+    spl_transfer_hook_interface::{
+        get_extra_account_metas_address,
+        instruction::{
+            ExecuteInstruction,
+            TransferHookInstruction
         }
     }
 };
 
 async fn setup(program_id: &Pubkey) -> ProgramTest {
-    let program_test = ProgramTest::new(
+    let mut program_test = ProgramTest::new(
         "solana_asset_splitter",
         *program_id,
         processor!(Processor::process)
     );
 
+    // program data of an upgradeable deployment, the config is initialized by its authority
+    let mut program_data = vec![3u8, 0u8, 0u8, 0u8];
+    program_data.extend_from_slice(&0u64.to_le_bytes());
+    program_data.push(1u8);
+    program_data.extend_from_slice(upgrade_authority().pubkey().as_ref());
+    program_test.add_account(
+        get_program_data_address(program_id),
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data: program_data,
+            owner: bpf_loader_upgradeable::ID,
+            ..Account::default()
+        }
+    );
+
     program_test
 }
 
+fn upgrade_authority() -> Keypair {
+    keypair_from_seed(&[7u8; 32]).unwrap()
+}
+
+async fn assert_splitter_error(
+    banks_client: &mut BanksClient,
+    tx: Transaction,
+    expected_error: SplitterError
+) {
+    let error = banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(expected_error as u32)
+        ),
+        "Unexpected error"
+    );
+}
+
+async fn get_token_account(
+    banks_client: &mut BanksClient,
+    token_account: &Pubkey
+) -> TokenAccount {
+    TokenAccount::unpack(
+        banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap()
+}
+
 async fn setup_single_mint(
     banks_client: &mut BanksClient,
     mint_account: &Keypair,
@@ -228,48 +411,56 @@ async fn setup_single_mint(
         .unwrap();
 }
 
-async fn setup_multiple_mints(
+async fn setup_multisig_token_account(
     banks_client: &mut BanksClient,
-    mint_one_account: &Keypair,
-    mint_two_account: &Keypair,
-    source_mint_one_token_account: &Keypair,
-    source_mint_two_token_account: &Keypair,
-    destination_mint_one_token_account: &Keypair,
-    destination_mint_two_token_account: &Keypair,
+    mint_account: &Keypair,
+    multisig_account: &Keypair,
+    token_account: &Keypair,
+    signers: &[&Keypair],
+    m: u8,
     owner: &Keypair,
     recent_blockhash: &Hash
 ) {
-    // 1. Create mint-accounts
-    // mint-account 1
+    // 1. create and initialize an m-of-n multisig_account
     let ix_1 = create_solana_account(
         &owner.pubkey(),
-        &mint_one_account.pubkey(),
-        Rent::default().minimum_balance(MintAccount::LEN),
-        MintAccount::LEN as u64,
+        &multisig_account.pubkey(),
+        Rent::default().minimum_balance(MultisigAccount::LEN),
+        MultisigAccount::LEN as u64,
         &TOKEN_STANDARD_PROGRAM
     );
-    let ix_2 = initialize_mint_account(
+    let signer_pubkeys = signers
+        .iter()
+        .map(|signer| signer.pubkey())
+        .collect::<Vec<Pubkey>>();
+    let ix_2 = initialize_multisig(
         &TOKEN_STANDARD_PROGRAM,
-        &mint_one_account.pubkey(),
-        &owner.pubkey(),
-        None,
-        3u8
+        &multisig_account.pubkey(),
+        &signer_pubkeys.iter().collect::<Vec<&Pubkey>>(),
+        m
     ).unwrap();
 
-    // mint-account 2
+    // 2. create and initialize a token account owned by the multisig and mint tokens to it
     let ix_3 = create_solana_account(
         &owner.pubkey(),
-        &mint_two_account.pubkey(),
-        Rent::default().minimum_balance(MintAccount::LEN),
-        MintAccount::LEN as u64,
+        &token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
         &TOKEN_STANDARD_PROGRAM
     );
-    let ix_4 = initialize_mint_account(
+    let ix_4 = initialize_token_account(
         &TOKEN_STANDARD_PROGRAM,
-        &mint_two_account.pubkey(),
+        &token_account.pubkey(),
+        &mint_account.pubkey(),
+        &multisig_account.pubkey()
+    ).unwrap();
+    let ix_5 = mint_to(
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_account.pubkey(),
+        &token_account.pubkey(),
         &owner.pubkey(),
-        None,
-        2u8
+        &[],
+        100_00u64
     ).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
@@ -277,13 +468,14 @@ async fn setup_multiple_mints(
             ix_1,
             ix_2,
             ix_3,
-            ix_4
+            ix_4,
+            ix_5
         ],
         Some(&owner.pubkey()),
         &[
             &owner,
-            &mint_one_account,
-            &mint_two_account
+            &multisig_account,
+            &token_account
         ],
         *recent_blockhash
     );
@@ -292,50 +484,40 @@ async fn setup_multiple_mints(
         .process_transaction(tx)
         .await
         .unwrap();
+}
 
-    // 2. Create source-token-accounts
-    // source-token-account one
+async fn setup_wrapped_sol_account(
+    banks_client: &mut BanksClient,
+    token_account: &Keypair,
+    token_account_owner: &Pubkey,
+    wrapped_lamports: u64,
+    owner: &Keypair,
+    recent_blockhash: &Hash
+) {
+    // create and initialize a native-mint token account holding `wrapped_lamports` on top of its rent
     let ix_1 = create_solana_account(
         &owner.pubkey(),
-        &source_mint_one_token_account.pubkey(),
-        Rent::default().minimum_balance(TokenAccount::LEN),
+        &token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN) + wrapped_lamports,
         TokenAccount::LEN as u64,
         &TOKEN_STANDARD_PROGRAM
     );
     let ix_2 = initialize_token_account(
         &TOKEN_STANDARD_PROGRAM,
-        &source_mint_one_token_account.pubkey(),
-        &mint_one_account.pubkey(),
-        &owner.pubkey()
-    ).unwrap();
-
-    // source-token-account two
-    let ix_3 = create_solana_account(
-        &owner.pubkey(),
-        &source_mint_two_token_account.pubkey(),
-        Rent::default().minimum_balance(TokenAccount::LEN),
-        TokenAccount::LEN as u64,
-        &TOKEN_STANDARD_PROGRAM
-    );
-    let ix_4 = initialize_token_account(
-        &TOKEN_STANDARD_PROGRAM,
-        &source_mint_two_token_account.pubkey(),
-        &mint_two_account.pubkey(),
-        &owner.pubkey()
+        &token_account.pubkey(),
+        &NATIVE_MINT,
+        token_account_owner
     ).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
         &[
             ix_1,
-            ix_2,
-            ix_3,
-            ix_4
+            ix_2
         ],
         Some(&owner.pubkey()),
         &[
             &owner,
-            &source_mint_one_token_account,
-            &source_mint_two_token_account
+            &token_account
         ],
         *recent_blockhash
     );
@@ -344,50 +526,98 @@ async fn setup_multiple_mints(
         .process_transaction(tx)
         .await
         .unwrap();
+}
 
-    // 3. Create destination-token-accounts
-    // destination-token-account one
+async fn get_token_2022_account(
+    banks_client: &mut BanksClient,
+    token_account: &Pubkey
+) -> TokenAccount2022 {
+    StateWithExtensions::<TokenAccount2022>::unpack(
+        banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap().base
+}
+
+async fn setup_single_mint_token_2022(
+    banks_client: &mut BanksClient,
+    mint_account: &Keypair,
+    token_accounts: &[&Keypair],
+    transfer_fee_basis_points: Option<u16>,
+    transfer_hook_program_id: Option<Pubkey>,
+    owner: &Keypair,
+    recent_blockhash: &Hash
+) {
+    // 1. create and initialize a mint_account carrying the mint-close-authority extension
+    // and, when requested, the transfer-fee-config and transfer-hook extensions
+    let mut mint_extensions = vec![ExtensionType::MintCloseAuthority];
+    if transfer_fee_basis_points.is_some() {
+        mint_extensions.push(ExtensionType::TransferFeeConfig);
+    };
+    if transfer_hook_program_id.is_some() {
+        mint_extensions.push(ExtensionType::TransferHook);
+    };
+    let mint_len = ExtensionType::try_calculate_account_len::<MintAccount2022>(
+        &mint_extensions
+    ).unwrap();
     let ix_1 = create_solana_account(
         &owner.pubkey(),
-        &destination_mint_one_token_account.pubkey(),
-        Rent::default().minimum_balance(TokenAccount::LEN),
-        TokenAccount::LEN as u64,
-        &TOKEN_STANDARD_PROGRAM
+        &mint_account.pubkey(),
+        Rent::default().minimum_balance(mint_len),
+        mint_len as u64,
+        &TOKEN_2022_PROGRAM
     );
-    let ix_2 = initialize_token_account(
-        &TOKEN_STANDARD_PROGRAM,
-        &destination_mint_one_token_account.pubkey(),
-        &mint_one_account.pubkey(),
-        &owner.pubkey()
+    let ix_2 = initialize_mint_close_authority(
+        &TOKEN_2022_PROGRAM,
+        &mint_account.pubkey(),
+        Some(&owner.pubkey())
     ).unwrap();
-
-    // destination-token-account two
-    let ix_3 = create_solana_account(
+    let ix_3 = initialize_mint_account(
+        &TOKEN_2022_PROGRAM,
+        &mint_account.pubkey(),
         &owner.pubkey(),
-        &destination_mint_two_token_account.pubkey(),
-        Rent::default().minimum_balance(TokenAccount::LEN),
-        TokenAccount::LEN as u64,
-        &TOKEN_STANDARD_PROGRAM
-    );
-    let ix_4 = initialize_token_account(
-        &TOKEN_STANDARD_PROGRAM,
-        &destination_mint_two_token_account.pubkey(),
-        &mint_two_account.pubkey(),
-        &owner.pubkey()
+        None,
+        2u8
     ).unwrap();
 
+    let mut ixs = vec![
+        ix_1,
+        ix_2
+    ];
+    if let Some(transfer_fee_basis_points) = transfer_fee_basis_points {
+        ixs.push(
+            initialize_transfer_fee_config(
+                &TOKEN_2022_PROGRAM,
+                &mint_account.pubkey(),
+                Some(&owner.pubkey()),
+                Some(&owner.pubkey()),
+                transfer_fee_basis_points,
+                u64::MAX
+            ).unwrap()
+        );
+    };
+    if transfer_hook_program_id.is_some() {
+        ixs.push(
+            initialize_transfer_hook(
+                &TOKEN_2022_PROGRAM,
+                &mint_account.pubkey(),
+                Some(owner.pubkey()),
+                transfer_hook_program_id
+            ).unwrap()
+        );
+    };
+    ixs.push(ix_3);
+
     let tx = Transaction::new_signed_with_payer(
-        &[
-            ix_1,
-            ix_2,
-            ix_3,
-            ix_4
-        ],
+        &ixs,
         Some(&owner.pubkey()),
         &[
             &owner,
-            &destination_mint_one_token_account,
-            &destination_mint_two_token_account
+            &mint_account
         ],
         *recent_blockhash
     );
@@ -397,15 +627,60 @@ async fn setup_multiple_mints(
         .await
         .unwrap();
 
-    // 4. Mint tokens to sourec-token-accounts
-    // mint to source-token-account one
+    // 2. create and initialize token accounts carrying the immutable-owner extension
+    // plus the extensions required by the mint
+    let mut account_extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+    account_extensions.push(ExtensionType::ImmutableOwner);
+    let account_len = ExtensionType::try_calculate_account_len::<TokenAccount2022>(
+        &account_extensions
+    ).unwrap();
+    for token_account in token_accounts.iter() {
+        let ix_1 = create_solana_account(
+            &owner.pubkey(),
+            &token_account.pubkey(),
+            Rent::default().minimum_balance(account_len),
+            account_len as u64,
+            &TOKEN_2022_PROGRAM
+        );
+        let ix_2 = initialize_immutable_owner(
+            &TOKEN_2022_PROGRAM,
+            &token_account.pubkey()
+        ).unwrap();
+        let ix_3 = initialize_token_account(
+            &TOKEN_2022_PROGRAM,
+            &token_account.pubkey(),
+            &mint_account.pubkey(),
+            &owner.pubkey()
+        ).unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                ix_1,
+                ix_2,
+                ix_3
+            ],
+            Some(&owner.pubkey()),
+            &[
+                &owner,
+                *token_account
+            ],
+            *recent_blockhash
+        );
+
+        banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap();
+    };
+
+    // 3. mint tokens to the first token account
     let ix = mint_to(
-        &TOKEN_STANDARD_PROGRAM,
-        &mint_one_account.pubkey(),
-        &source_mint_one_token_account.pubkey(),
+        &TOKEN_2022_PROGRAM,
+        &mint_account.pubkey(),
+        &token_accounts[0].pubkey(),
         &owner.pubkey(),
-        &[&owner.pubkey()],
-        1000_000u64
+        &[],
+        100_00u64
     ).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
@@ -419,21 +694,68 @@ async fn setup_multiple_mints(
         .process_transaction(tx)
         .await
         .unwrap();
+}
 
-    // mint to source-token-account two
-    let ix = mint_to(
-        &TOKEN_STANDARD_PROGRAM,
-        &mint_two_account.pubkey(),
-        &source_mint_two_token_account.pubkey(),
+/// Transfer hook that counts its executions in the extra account it requires
+fn process_mock_transfer_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8]
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(instruction_data)? {
+        TransferHookInstruction::Execute { .. } => {
+            // 0. source 1. mint 2. destination 3. authority 4. validation account 5. counter
+            let counter = accounts
+                .get(5)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if counter.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            };
+
+            counter.try_borrow_mut_data()?[0] += 1;
+
+            Ok(())
+        },
+        _ => Err(ProgramError::InvalidInstructionData)
+    }
+}
+
+fn setup_memo_program(program_test: &mut ProgramTest) {
+    program_test.add_program(
+        "spl_memo",
+        spl_memo::ID,
+        processor!(spl_memo::processor::process_instruction)
+    );
+}
+
+async fn setup_required_transfer_memos(
+    banks_client: &mut BanksClient,
+    token_account: &Pubkey,
+    owner: &Keypair,
+    recent_blockhash: &Hash
+) {
+    let ix_1 = reallocate(
+        &TOKEN_2022_PROGRAM,
+        token_account,
         &owner.pubkey(),
-        &[&owner.pubkey()],
-        500_00u64
+        &owner.pubkey(),
+        &[],
+        &[ExtensionType::MemoTransfer]
+    ).unwrap();
+    let ix_2 = enable_required_transfer_memos(
+        &TOKEN_2022_PROGRAM,
+        token_account,
+        &owner.pubkey(),
+        &[]
     ).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
-        &[ix],
+        &[
+            ix_1,
+            ix_2
+        ],
         Some(&owner.pubkey()),
-        &[&owner],
+        &[owner],
         *recent_blockhash
     );
 
@@ -443,166 +765,7156 @@ async fn setup_multiple_mints(
         .unwrap();
 }
 
-#[tokio::test]
-async fn success_splitlamports() {
-    let program_id = Pubkey::new_unique();
-    let pt = setup(&program_id).await;
-    let (
-        mut banks_client,
-        operator,
-        recent_blockhash
-    ) = pt.start().await;
+async fn setup_config(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey,
+    admin: &Keypair,
+    fee_basis_points: u16,
+    treasury: &Pubkey,
+    timelock_delay: u32,
+    recent_blockhash: &Hash
+) {
+    let upgrade_authority = upgrade_authority();
+    let ix = initialize_config(
+        program_id,
+        &admin.pubkey(),
+        &upgrade_authority.pubkey(),
+        &admin.pubkey(),
+        fee_basis_points,
+        treasury,
+        timelock_delay
+    ).unwrap();
 
-    // 1. data
-    let amounts: Vec<u64> = vec![
-        sol_to_lamports(1.1),
-        sol_to_lamports(1.2),
-        sol_to_lamports(1.3)
-    ];
-    let mut data: Vec<u8> = vec![];
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
-    );
-    data.append(
-        &mut SplitLamports { amounts }
-        .try_to_vec()
-        .unwrap()
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[admin, &upgrade_authority],
+        *recent_blockhash
     );
 
-    // 2. provide accounts
-    let accounts: Vec<Pubkey> = vec![
-        Keypair::new().pubkey(),
-        Keypair::new().pubkey(),
-        Keypair::new().pubkey()
-    ]; 
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+}
+
+async fn get_config(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey
+) -> Config {
+    Config::unpack(
+        &banks_client
+            .get_account(get_config_address(program_id).0)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+    ).unwrap()
+}
+
+async fn setup_policy(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey,
+    admin: &Keypair,
+    kind: PolicyKind,
+    addresses: Vec<Pubkey>,
+    recent_blockhash: &Hash
+) {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            initialize_policy(program_id, &admin.pubkey(), &admin.pubkey(), kind).unwrap(),
+            add_policy_addresses(program_id, &admin.pubkey(), &admin.pubkey(), kind, addresses).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[admin],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+}
+
+async fn setup_plan(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey,
+    authority: &Keypair,
+    asset: PlanAsset,
+    recipients: Vec<Pubkey>,
+    allocation: PlanAllocation,
+    recent_blockhash: &Hash
+) -> SplitPlan {
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_plan(
+                program_id,
+                &authority.pubkey(),
+                &authority.pubkey(),
+                0u64,
+                asset,
+                recipients,
+                allocation
+            ).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[authority],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    get_plan(banks_client, program_id, &authority.pubkey()).await
+}
+
+async fn get_plan(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey,
+    authority: &Pubkey
+) -> SplitPlan {
+    SplitPlan::unpack(
+        &banks_client
+            .get_account(get_plan_address(program_id, authority, 0u64).0)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+    ).unwrap()
+}
+
+fn setup_mock_transfer_hook(
+    program_test: &mut ProgramTest,
+    hook_program_id: &Pubkey,
+    mint: &Pubkey,
+    counter: &Pubkey
+) {
+    program_test.add_program(
+        "mock_transfer_hook",
+        *hook_program_id,
+        processor!(process_mock_transfer_hook)
+    );
+
+    // the validation account requires the counter as a writable extra account
+    let validation_len = ExtraAccountMetaList::size_of(1).unwrap();
+    let mut validation_data = vec![0u8; validation_len];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut validation_data,
+        &[ExtraAccountMeta::new_with_pubkey(counter, false, true).unwrap()]
+    ).unwrap();
+
+    program_test.add_account(
+        get_extra_account_metas_address(mint, hook_program_id),
+        Account {
+            lamports: Rent::default().minimum_balance(validation_len),
+            data: validation_data,
+            owner: *hook_program_id,
+            ..Account::default()
+        }
+    );
+    program_test.add_account(
+        *counter,
+        Account {
+            lamports: Rent::default().minimum_balance(1),
+            data: vec![0u8],
+            owner: *hook_program_id,
+            ..Account::default()
+        }
+    );
+}
+
+async fn setup_multiple_mints(
+    banks_client: &mut BanksClient,
+    mint_one_account: &Keypair,
+    mint_two_account: &Keypair,
+    source_mint_one_token_account: &Keypair,
+    source_mint_two_token_account: &Keypair,
+    destination_mint_one_token_account: &Keypair,
+    destination_mint_two_token_account: &Keypair,
+    owner: &Keypair,
+    recent_blockhash: &Hash
+) {
+    // 1. Create mint-accounts
+    // mint-account 1
+    let ix_1 = create_solana_account(
+        &owner.pubkey(),
+        &mint_one_account.pubkey(),
+        Rent::default().minimum_balance(MintAccount::LEN),
+        MintAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_2 = initialize_mint_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_one_account.pubkey(),
+        &owner.pubkey(),
+        None,
+        3u8
+    ).unwrap();
+
+    // mint-account 2
+    let ix_3 = create_solana_account(
+        &owner.pubkey(),
+        &mint_two_account.pubkey(),
+        Rent::default().minimum_balance(MintAccount::LEN),
+        MintAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_4 = initialize_mint_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_two_account.pubkey(),
+        &owner.pubkey(),
+        None,
+        2u8
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ix_1,
+            ix_2,
+            ix_3,
+            ix_4
+        ],
+        Some(&owner.pubkey()),
+        &[
+            &owner,
+            &mint_one_account,
+            &mint_two_account
+        ],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. Create source-token-accounts
+    // source-token-account one
+    let ix_1 = create_solana_account(
+        &owner.pubkey(),
+        &source_mint_one_token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_2 = initialize_token_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &source_mint_one_token_account.pubkey(),
+        &mint_one_account.pubkey(),
+        &owner.pubkey()
+    ).unwrap();
+
+    // source-token-account two
+    let ix_3 = create_solana_account(
+        &owner.pubkey(),
+        &source_mint_two_token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_4 = initialize_token_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &source_mint_two_token_account.pubkey(),
+        &mint_two_account.pubkey(),
+        &owner.pubkey()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ix_1,
+            ix_2,
+            ix_3,
+            ix_4
+        ],
+        Some(&owner.pubkey()),
+        &[
+            &owner,
+            &source_mint_one_token_account,
+            &source_mint_two_token_account
+        ],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 3. Create destination-token-accounts
+    // destination-token-account one
+    let ix_1 = create_solana_account(
+        &owner.pubkey(),
+        &destination_mint_one_token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_2 = initialize_token_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &destination_mint_one_token_account.pubkey(),
+        &mint_one_account.pubkey(),
+        &owner.pubkey()
+    ).unwrap();
+
+    // destination-token-account two
+    let ix_3 = create_solana_account(
+        &owner.pubkey(),
+        &destination_mint_two_token_account.pubkey(),
+        Rent::default().minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        &TOKEN_STANDARD_PROGRAM
+    );
+    let ix_4 = initialize_token_account(
+        &TOKEN_STANDARD_PROGRAM,
+        &destination_mint_two_token_account.pubkey(),
+        &mint_two_account.pubkey(),
+        &owner.pubkey()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ix_1,
+            ix_2,
+            ix_3,
+            ix_4
+        ],
+        Some(&owner.pubkey()),
+        &[
+            &owner,
+            &destination_mint_one_token_account,
+            &destination_mint_two_token_account
+        ],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 4. Mint tokens to sourec-token-accounts
+    // mint to source-token-account one
+    let ix = mint_to(
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_one_account.pubkey(),
+        &source_mint_one_token_account.pubkey(),
+        &owner.pubkey(),
+        &[&owner.pubkey()],
+        1000_000u64
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // mint to source-token-account two
+    let ix = mint_to(
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_two_account.pubkey(),
+        &source_mint_two_token_account.pubkey(),
+        &owner.pubkey(),
+        &[&owner.pubkey()],
+        500_00u64
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        *recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn success_splitlamports() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2),
+        sol_to_lamports(1.3)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide accounts
+    let accounts: Vec<Pubkey> = vec![
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey()
+    ]; 
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(*accounts.get(0_usize).unwrap(), false),
+        AccountMeta::new(*accounts.get(1_usize).unwrap(), false),
+        AccountMeta::new(*accounts.get(2_usize).unwrap(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    let operator_balance_before = banks_client.get_balance(operator.pubkey())
+        .await
+        .unwrap();
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let account_1 = banks_client.get_account(*accounts.get(0usize).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    let account_2 = banks_client.get_account(*accounts.get(1usize).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    let account_3 = banks_client.get_account(*accounts.get(2usize).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        account_1.lamports,
+        sol_to_lamports(1.1),
+        "Mismacth account_1 balance"
+    );
+    assert_eq!(
+        account_2.lamports,
+        sol_to_lamports(1.2),
+        "Mismacth account_2 balance"
+    );
+    assert_eq!(
+        account_3.lamports,
+        sol_to_lamports(1.3),
+        "Mismacth account_3 balance"
+    );
+
+    let operator_balance_after = banks_client.get_balance(operator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        operator_balance_before - (sol_to_lamports(3.6) + 5000),
+        operator_balance_after,
+        "Mismatch operator balance"
+    );
+}
+
+#[tokio::test]
+async fn fail_splitlamports() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2),
+        sol_to_lamports(1.3)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide accounts
+    let accounts: Vec<Pubkey> = vec![
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey()
+    ]; 
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(*accounts.get(0_usize).unwrap(), false),
+        AccountMeta::new(*accounts.get(1_usize).unwrap(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // test owner instruction
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    // 4. send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // Checks...
+    let mint_account_info = banks_client.get_account(mint_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mint_account = MintAccount::unpack(
+        mint_account_info.data.as_slice()
+    ).unwrap();
+    let source_token_account = TokenAccount::unpack(
+        source_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_token_account = TokenAccount::unpack(
+        destination_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_second_token_account = TokenAccount::unpack(
+        destination_second_token_account_info.data.as_slice()
+    ).unwrap();
+
+    assert_eq!(
+        mint_account.supply,
+        100_00u64,
+        "Mint's supply mismatch."
+    );
+    assert_eq!(
+        source_token_account.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        destination_token_account.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        destination_second_token_account.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_2() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // test owner instruction
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        50_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    // 4. send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // Checks...
+    let mint_account_info = banks_client.get_account(mint_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mint_account = MintAccount::unpack(
+        mint_account_info.data.as_slice()
+    ).unwrap();
+    let source_token_account = TokenAccount::unpack(
+        source_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_token_account = TokenAccount::unpack(
+        destination_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_second_token_account = TokenAccount::unpack(
+        destination_second_token_account_info.data.as_slice()
+    ).unwrap();
+
+    assert_eq!(
+        mint_account.supply,
+        100_00u64,
+        "Mint's supply mismatch."
+    );
+    assert_eq!(
+        source_token_account.amount,
+        50_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        destination_token_account.amount,
+        50_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        destination_second_token_account.amount,
+        0_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // test owner instruction
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    // 4. send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    // Checks...
+    let mint_account_info = banks_client.get_account(mint_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mint_account = MintAccount::unpack(
+        mint_account_info.data.as_slice()
+    ).unwrap();
+    let source_token_account = TokenAccount::unpack(
+        source_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_token_account = TokenAccount::unpack(
+        destination_token_account_info.data.as_slice()
+    ).unwrap();
+    let destination_second_token_account = TokenAccount::unpack(
+        destination_second_token_account_info.data.as_slice()
+    ).unwrap();
+
+    assert_eq!(
+        mint_account.supply,
+        100_00u64,
+        "Mint's supply mismatch."
+    );
+    assert_eq!(
+        source_token_account.amount,
+        100_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        destination_token_account.amount,
+        0_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        destination_second_token_account.amount,
+        0_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitefrommultiplemintaccounts() {
+    let program_id = Pubkey::new_from_array([5u8; 32]);
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+    
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. Create instruction
+    // provide data
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
+    );
+
+    let amounts: Vec<u64> = vec![
+        100_000u64,
+        50_00u64
+    ];
+    let m = 2u16;
+
+    data.append(
+        &mut SplitSplTokensFromMultipleMints {
+            amounts,
+            m,
+            decimals: vec![
+                3u8,
+                2u8
+            ],
+            options: TokenSplitOptions::default()
+        }.try_to_vec().unwrap()
+    );
+
+    // provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_2.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_1.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_2.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let ix = Instruction {
+        program_id,
+        data,
+        accounts
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let source_token_account_mint_1 = TokenAccount::unpack(
+        banks_client
+            .get_account(src_token_account_mint_1.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap();
+    let source_token_account_mint_2 = TokenAccount::unpack(
+        banks_client
+            .get_account(src_token_account_mint_2.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap();
+    let destination_token_account_mint_1 = TokenAccount::unpack(
+        banks_client
+            .get_account(dst_token_account_mint_1.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap();
+    let destination_token_account_mint_2 = TokenAccount::unpack(
+        banks_client
+            .get_account(dst_token_account_mint_2.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice()
+    ).unwrap();
+
+    assert_eq!(
+        source_token_account_mint_1.amount,
+        (1000_000 - 100_000) as u64,
+        "Source token account mint-1 token balance mismatch."
+    );
+    assert_eq!(
+        source_token_account_mint_2.amount,
+        (500_00 - 50_00) as u64,
+        "Source token account mint-2 token balance mismatch."
+    );
+    assert_eq!(
+        destination_token_account_mint_1.amount,
+        100_000u64,
+        "Destination token account mint-1 token balance mismatch."
+    );
+    assert_eq!(
+        destination_token_account_mint_2.amount,
+        50_00u64,
+        "Destination token account mint-2 token balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitefrommultiplemintaccounts() {
+    let program_id = Pubkey::new_from_array([5u8; 32]);
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+    
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. Create instruction
+    // provide data
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
+    );
+
+    let amounts: Vec<u64> = vec![
+        100_000u64,
+        50_00u64
+    ];
+    let m = 1u16;
+
+    data.append(
+        &mut SplitSplTokensFromMultipleMints {
+            amounts,
+            m,
+            decimals: vec![
+                3u8,
+                2u8
+            ],
+            options: TokenSplitOptions::default()
+        }.try_to_vec().unwrap()
+    );
+
+    // provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_2.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_1.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_2.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let ix = Instruction {
+        program_id,
+        data,
+        accounts
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidMParameter
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitefrommultiplemintaccounts_2() {
+    let program_id = Pubkey::new_from_array([5u8; 32]);
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+    
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. Create instruction
+    // provide data
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
+    );
+
+    let amounts: Vec<u64> = vec![
+        100_000u64,
+        50_00u64,
+        100_00u64
+    ];
+    let m = 2u16;
+
+    data.append(
+        &mut SplitSplTokensFromMultipleMints {
+            amounts,
+            m,
+            decimals: vec![
+                3u8,
+                2u8
+            ],
+            options: TokenSplitOptions::default()
+        }.try_to_vec().unwrap()
+    );
+
+    // provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_2.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_1.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_2.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let ix = Instruction {
+        program_id,
+        data,
+        accounts
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidMParameter
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_insufficient_lamports() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. fund the source account with less than the requested total
+    let source = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            transfer_lamports(
+                &payer.pubkey(),
+                &source.pubkey(),
+                sol_to_lamports(1.0)
+            )
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(0.6),
+        sol_to_lamports(0.6)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 3. provide accounts
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(source.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &source],
+        recent_blockhash
+    );
+
+    let error = banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(SplitterError::InsufficientLamports as u32)
+        ),
+        "Unexpected error"
+    );
+}
+
+#[tokio::test]
+async fn fail_unknown_instruction() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:unknown").as_slice()
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: vec![]
+            }
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash
+    );
+
+    let error = banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(SplitterError::InvalidInstruction as u32)
+        ),
+        "Unexpected error"
+    );
+}
+
+#[tokio::test]
+async fn fail_splitlamports_duplicate_destination() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide the same destination twice
+    let destination = Keypair::new().pubkey();
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(destination, false),
+        AccountMeta::new(destination, false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::DuplicateDestination
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_destination_is_source() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide the source as a destination
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new(operator.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::DestinationIsSource
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_account_not_writable() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide a read-only destination
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::AccountNotWritable
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_invalid_system_program() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![
+        sol_to_lamports(1.1),
+        sol_to_lamports(1.2)
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide a fake system program
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidSystemProgram
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_accounts_amounts_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let amounts: Vec<u64> = vec![sol_to_lamports(0.1); 8];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
+    );
+    data.append(
+        &mut SplitLamports {
+            amounts,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide fewer accounts after the system program than amounts, the config and policies included
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::AccountsAmountsMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_invalid_token_program() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts with a fake token program
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidTokenProgram
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitbyroutes_fan_out() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. one source fans out to both destinations
+    let routes: Vec<TransferRoute> = vec![
+        TransferRoute {
+            source_index: 0,
+            destination_index: 1,
+            mint_index: 3,
+            amount: 10_00u64,
+            decimals: 2u8
+        },
+        TransferRoute {
+            source_index: 0,
+            destination_index: 2,
+            mint_index: 3,
+            amount: 20_00u64,
+            decimals: 2u8
+        }
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensbyroutes").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensByRoutes {
+            routes,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitbyroutes_multiple_mints() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. routes are listed out of account order on purpose
+    let routes: Vec<TransferRoute> = vec![
+        TransferRoute {
+            source_index: 1,
+            destination_index: 3,
+            mint_index: 5,
+            amount: 50_00u64,
+            decimals: 2u8
+        },
+        TransferRoute {
+            source_index: 0,
+            destination_index: 2,
+            mint_index: 4,
+            amount: 100_000u64,
+            decimals: 3u8
+        }
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensbyroutes").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensByRoutes {
+            routes,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_2.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_1.pubkey(), false),
+        AccountMeta::new_readonly(mint_account_2.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account_mint_1.pubkey()).await.amount,
+        100_000u64,
+        "Destination token account mint-1 token balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account_mint_2.pubkey()).await.amount,
+        50_00u64,
+        "Destination token account mint-2 token balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitbyroutes_index_out_of_bounds() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. the second route points past the provided accounts
+    let routes: Vec<TransferRoute> = vec![
+        TransferRoute {
+            source_index: 0,
+            destination_index: 1,
+            mint_index: 3,
+            amount: 10_00u64,
+            decimals: 2u8
+        },
+        TransferRoute {
+            source_index: 0,
+            destination_index: 7,
+            mint_index: 3,
+            amount: 20_00u64,
+            decimals: 2u8
+        }
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensbyroutes").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensByRoutes {
+            routes,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RouteIndexOutOfBounds
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitlamportsbyshares() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data, one lamport is left over after the equal split
+    let total = sol_to_lamports(1.0);
+    let shares: Vec<u64> = vec![
+        1u64,
+        1u64,
+        1u64
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamportsbyshares").as_slice()
+    );
+    data.append(
+        &mut SplitLamportsByShares {
+            total,
+            shares,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide accounts
+    let accounts: Vec<Pubkey> = vec![
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey()
+    ];
     let keys: Vec<AccountMeta> = vec![
         AccountMeta::new(operator.pubkey(), true),
         AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-        AccountMeta::new(*accounts.get(0_usize).unwrap(), false),
-        AccountMeta::new(*accounts.get(1_usize).unwrap(), false),
-        AccountMeta::new(*accounts.get(2_usize).unwrap(), false)
+        AccountMeta::new(accounts[0], false),
+        AccountMeta::new(accounts[1], false),
+        AccountMeta::new(accounts[2], false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // the leftover lamport goes to the first destination
+    let expected: Vec<u64> = vec![
+        333_333_334u64,
+        333_333_333u64,
+        333_333_333u64
+    ];
+    for (account, expected_balance) in accounts.iter().zip(expected.iter()) {
+        assert_eq!(
+            banks_client.get_balance(*account).await.unwrap(),
+            *expected_balance,
+            "Mismatch destination balance"
+        );
+    };
+}
+
+#[tokio::test]
+async fn fail_splitlamportsbyshares_zero_shares() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        operator,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. data
+    let total = sol_to_lamports(1.0);
+    let shares: Vec<u64> = vec![
+        0u64,
+        0u64
+    ];
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitlamportsbyshares").as_slice()
+    );
+    data.append(
+        &mut SplitLamportsByShares {
+            total,
+            shares,
+            options: LamportSplitOptions::default()
+        }
+            .try_to_vec()
+        .unwrap()
+    );
+
+    // 2. provide accounts
+    let keys: Vec<AccountMeta> = vec![
+        AccountMeta::new(operator.pubkey(), true),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new(Keypair::new().pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts: keys
+            }
+        ],
+        Some(&operator.pubkey()),
+        &[&operator],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidShares
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemintbyshares_sweep() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    // total is ignored, the whole 100_00 balance is split 1:3
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemintbyshares").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMintByShares {
+            total: 0u64,
+            shares: vec![1u64, 3u64],
+            sweep: true,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    // 4. send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        0u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        25_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        75_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemintbyshares_total() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    // 10_01 split 1:1, the leftover unit goes to the first destination
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemintbyshares").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMintByShares {
+            total: 10_01u64,
+            shares: vec![1u64, 1u64],
+            sweep: false,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    // 4. send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        89_99u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        5_01u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        5_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_decimals_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data, the mint has 2 decimals
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 3u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MintDecimalsMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_mint_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 3u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. the second destination holds tokens of mint-2
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account_1.pubkey(), false),
+        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
+        AccountMeta::new(dst_token_account_mint_2.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MintMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_token_2022() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        None,
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemintbyshares_token_2022_sweep() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        None,
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. sweep the whole balance 1:1
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemintbyshares").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMintByShares {
+            total: 0u64,
+            shares: vec![1u64, 1u64],
+            sweep: true,
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        0u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        50_00u64,
+        "Destination token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_token_program_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account
+        ],
+        None,
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide data
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts: vec![10_00u64],
+            decimals: 2u8,
+            options: TokenSplitOptions::default()
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. the spl-token program does not own the token-2022 accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidMint
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_net_of_transfer_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup a token-2022 mint charging a 1% transfer fee
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        Some(100u16),
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide net amounts
+    let amounts: Vec<u64> = vec![
+        10_00u64,
+        20_00u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions {
+                net_amounts: true,
+                ..TokenSplitOptions::default()
+            }
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // recipients get the net amounts, the source pays 10_11 + 20_21
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        69_68u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_net_of_transfer_fee_insufficient() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup a token-2022 mint charging a 1% transfer fee
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        Some(100u16),
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. provide net amounts
+    let amounts: Vec<u64> = vec![
+        50_00u64,
+        49_50u64
+    ];
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(
+        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    );
+    data.append(
+        &mut SplitSplTokensFromSingleMint {
+            amounts,
+            decimals: 2u8,
+            options: TokenSplitOptions {
+                net_amounts: true,
+                ..TokenSplitOptions::default()
+            }
+        }
+            .try_to_vec()
+            .unwrap()
+    );
+
+    // 3. provide accounts
+    let accounts: Vec<AccountMeta> = vec![
+        AccountMeta::new_readonly(owner.pubkey(), true),
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),
+        AccountMeta::new_readonly(mint_account.pubkey(), false),
+        AccountMeta::new(src_token_account.pubkey(), false),
+        AccountMeta::new(dst_token_account.pubkey(), false),
+        AccountMeta::new(dst_sc_token_account.pubkey(), false),
+        AccountMeta::new_readonly(get_config_address(&program_id).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::MintAllowlist).0, false),
+        AccountMeta::new_readonly(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0, false)
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            Instruction {
+                program_id,
+                data,
+                accounts
+            }
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    // 99_50 net fits the balance but the grossed-up 100_51 does not
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InsufficientTokens
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_transfer_hook() {
+    let program_id = Pubkey::new_unique();
+    let hook_program_id = Pubkey::new_unique();
+    let counter = Pubkey::new_unique();
+    let mint_account = Keypair::new();
+    let mut pt = setup(&program_id).await;
+    setup_mock_transfer_hook(
+        &mut pt,
+        &hook_program_id,
+        &mint_account.pubkey(),
+        &counter
+    );
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022, the mint carries the mock hook
+    let owner = payer;
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        None,
+        Some(hook_program_id),
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. build the instruction, the hook accounts are resolved from the chain
+    let ix = split_spl_tokens_from_single_mint_with_extra_metas(
+        |address| {
+            let mut banks_client = banks_client.clone();
+            async move {
+                banks_client
+                    .get_account(address)
+                    .await
+                    .map(|account| account.map(|account| account.data))
+                    .map_err(Into::into)
+            }
+        },
+        &program_id,
+        &TOKEN_2022_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).await.unwrap();
+
+    assert!(
+        ix.accounts.contains(&AccountMeta::new(counter, false)),
+        "Transfer hook extra account was not resolved."
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_account(counter).await.unwrap().unwrap().data[0],
+        2u8,
+        "Transfer hook was not executed once per leg."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_transfer_hook_accounts_missing() {
+    let program_id = Pubkey::new_unique();
+    let hook_program_id = Pubkey::new_unique();
+    let counter = Pubkey::new_unique();
+    let mint_account = Keypair::new();
+    let mut pt = setup(&program_id).await;
+    setup_mock_transfer_hook(
+        &mut pt,
+        &hook_program_id,
+        &mint_account.pubkey(),
+        &counter
+    );
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022, the mint carries the mock hook
+    let owner = payer;
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account
+        ],
+        None,
+        Some(hook_program_id),
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. build the instruction without resolving the hook accounts
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_2022_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[dst_token_account.pubkey()],
+        vec![10_00u64],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MissingTransferHookAccounts
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_to_wallets() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, only the second wallet already has its associated token account
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+    let wallet = Pubkey::new_unique();
+    let sc_wallet = Pubkey::new_unique();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_associated_token_account_idempotent(
+                &owner.pubkey(),
+                &sc_wallet,
+                &mint_account.pubkey(),
+                &TOKEN_STANDARD_PROGRAM
+            )
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. split to the wallets, the owner pays the rent of the missing associated token account
+    let ix = split_spl_tokens_from_single_mint_to_wallets(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &owner.pubkey(),
+        &[
+            wallet,
+            sc_wallet
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(
+            &mut banks_client,
+            &get_associated_token_address(&wallet, &mint_account.pubkey())
+        ).await.amount,
+        10_00u64,
+        "Created associated token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(
+            &mut banks_client,
+            &get_associated_token_address(&sc_wallet, &mint_account.pubkey())
+        ).await.amount,
+        20_00u64,
+        "Existing associated token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_to_wallets_non_canonical_destination() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. pay the owner's wallet into a token account that is not its associated token account
+    let mut ix = split_spl_tokens_from_single_mint_to_wallets(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &owner.pubkey(),
+        &[owner.pubkey()],
+        vec![10_00u64],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+    ix.accounts[8].pubkey = dst_token_account.pubkey();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidAssociatedTokenAccount
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_to_wallets_mint_decimals_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. the wrong decimals are rejected before the token account of the wallet is created
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            split_spl_tokens_from_single_mint_to_wallets(
+                &program_id,
+                &TOKEN_STANDARD_PROGRAM,
+                &owner.pubkey(),
+                &[],
+                &mint_account.pubkey(),
+                &src_token_account.pubkey(),
+                &owner.pubkey(),
+                &[Pubkey::new_unique()],
+                vec![10_00u64],
+                3u8,
+                TokenSplitOptions::default()
+            ).unwrap()
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MintDecimalsMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_multisig_operator() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, the source is owned by a 3-of-5 multisig
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+    let multisig_account = Keypair::new();
+    let multisig_token_account = Keypair::new();
+    let signers = (0..5)
+        .map(|_| Keypair::new())
+        .collect::<Vec<Keypair>>();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_multisig_token_account(
+        &mut banks_client,
+        &mint_account,
+        &multisig_account,
+        &multisig_token_account,
+        &signers.iter().collect::<Vec<&Keypair>>(),
+        3u8,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split from the multisig owned token account, 3 of the signers sign
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &multisig_account.pubkey(),
+        &[
+            &signers[0].pubkey(),
+            &signers[1].pubkey(),
+            &signers[2].pubkey()
+        ],
+        &mint_account.pubkey(),
+        &multisig_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[
+            &owner,
+            &signers[0],
+            &signers[1],
+            &signers[2]
+        ],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &multisig_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_multisig_operator_missing_signers() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, the source is owned by a 3-of-5 multisig
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+    let multisig_account = Keypair::new();
+    let multisig_token_account = Keypair::new();
+    let signers = (0..5)
+        .map(|_| Keypair::new())
+        .collect::<Vec<Keypair>>();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_multisig_token_account(
+        &mut banks_client,
+        &mint_account,
+        &multisig_account,
+        &multisig_token_account,
+        &signers.iter().collect::<Vec<&Keypair>>(),
+        3u8,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split from the multisig owned token account, 2 of the signers sign
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &multisig_account.pubkey(),
+        &[
+            &signers[0].pubkey(),
+            &signers[1].pubkey()
+        ],
+        &mint_account.pubkey(),
+        &multisig_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[
+            &owner,
+            &signers[0],
+            &signers[1]
+        ],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MissingRequiredSignature
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitlamportstowrappedsol() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup empty native-mint token accounts of two recipients
+    let owner = payer;
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    for dst in [&dst_token_account, &dst_sc_token_account] {
+        setup_wrapped_sol_account(
+            &mut banks_client,
+            dst,
+            &Pubkey::new_unique(),
+            0u64,
+            &owner,
+            &recent_blockhash
+        ).await;
+    };
+
+    // 2. wrap lamports of the owner into the recipients' token accounts
+    let ix = split_lamports_to_wrapped_sol(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        sol_to_lamports(1.0),
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        sol_to_lamports(2.0),
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitwrappedsoltolamports() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup the owner's wrapped SOL source and an empty scratch account
+    let owner = payer;
+    let src_token_account = Keypair::new();
+    let scratch_token_account = Keypair::new();
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    setup_wrapped_sol_account(
+        &mut banks_client,
+        &src_token_account,
+        &owner.pubkey(),
+        sol_to_lamports(5.0),
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_wrapped_sol_account(
+        &mut banks_client,
+        &scratch_token_account,
+        &owner.pubkey(),
+        0u64,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. unwrap and pay the recipients in lamports
+    let ix = split_wrapped_sol_to_lamports(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &src_token_account.pubkey(),
+        &scratch_token_account.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        sol_to_lamports(2.0),
+        "Source token-account balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination account balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(2.0),
+        "Destination second account balance mismatch."
+    );
+    assert!(
+        banks_client.get_account(scratch_token_account.pubkey()).await.unwrap().is_none(),
+        "Scratch token account was not closed."
+    );
+}
+
+#[tokio::test]
+async fn success_splitmixedassets() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+    let dst_account = Pubkey::new_unique();
+
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. pay one contributor lamports plus tokens of both mints
+    let ix = split_mixed_assets(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &[
+            dst_account,
+            src_token_account_mint_1.pubkey(),
+            src_token_account_mint_2.pubkey(),
+            dst_token_account_mint_1.pubkey(),
+            dst_token_account_mint_2.pubkey(),
+            mint_account_1.pubkey(),
+            mint_account_2.pubkey()
+        ],
+        vec![
+            SplitLeg::Lamports {
+                destination_index: 0,
+                amount: sol_to_lamports(1.0)
+            },
+            SplitLeg::Token(
+                TransferRoute {
+                    source_index: 1,
+                    destination_index: 3,
+                    mint_index: 5,
+                    amount: 100_000u64,
+                    decimals: 3u8
+                }
+            ),
+            SplitLeg::Token(
+                TransferRoute {
+                    source_index: 2,
+                    destination_index: 4,
+                    mint_index: 6,
+                    amount: 50_00u64,
+                    decimals: 2u8
+                }
+            )
+        ],
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account_mint_1.pubkey()).await.amount,
+        100_000u64,
+        "Destination token account mint-1 token balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account_mint_2.pubkey()).await.amount,
+        50_00u64,
+        "Destination token account mint-2 token balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitmixedassets_lamports_index_out_of_bounds() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account_1 = Keypair::new();
+    let mint_account_2 = Keypair::new();
+    let src_token_account_mint_1 = Keypair::new();
+    let src_token_account_mint_2 = Keypair::new();
+    let dst_token_account_mint_1 = Keypair::new();
+    let dst_token_account_mint_2 = Keypair::new();
+    let dst_account = Pubkey::new_unique();
+
+    setup_multiple_mints(
+        &mut banks_client,
+        &mint_account_1,
+        &mint_account_2,
+        &src_token_account_mint_1,
+        &src_token_account_mint_2,
+        &dst_token_account_mint_1,
+        &dst_token_account_mint_2,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. pay one contributor lamports plus tokens of both mints
+    let ix = split_mixed_assets(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &[
+            dst_account,
+            src_token_account_mint_1.pubkey(),
+            src_token_account_mint_2.pubkey(),
+            dst_token_account_mint_1.pubkey(),
+            dst_token_account_mint_2.pubkey(),
+            mint_account_1.pubkey(),
+            mint_account_2.pubkey()
+        ],
+        vec![
+            SplitLeg::Lamports {
+                destination_index: 10,
+                amount: sol_to_lamports(1.0)
+            },
+            SplitLeg::Token(
+                TransferRoute {
+                    source_index: 1,
+                    destination_index: 3,
+                    mint_index: 5,
+                    amount: 100_000u64,
+                    decimals: 3u8
+                }
+            ),
+            SplitLeg::Token(
+                TransferRoute {
+                    source_index: 2,
+                    destination_index: 4,
+                    mint_index: 6,
+                    amount: 50_00u64,
+                    decimals: 2u8
+                }
+            )
+        ],
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RouteIndexOutOfBounds
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitmixedassets_invalid_system_program() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. pay a lamport leg through a fake system program
+    let owner = payer;
+    let mut ix = split_mixed_assets(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &[Pubkey::new_unique()],
+        vec![
+            SplitLeg::Lamports {
+                destination_index: 0,
+                amount: sol_to_lamports(1.0)
+            }
+        ],
+        TokenSplitOptions::default()
+    ).unwrap();
+    ix.accounts[2] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidSystemProgram
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemintbyshares_sweep_close_source() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup the owner's wrapped SOL source and the recipients' native-mint token accounts
+    let owner = payer;
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_wrapped_sol_account(
+        &mut banks_client,
+        &src_token_account,
+        &owner.pubkey(),
+        sol_to_lamports(3.0),
+        &owner,
+        &recent_blockhash
+    ).await;
+    for dst in [&dst_token_account, &dst_sc_token_account] {
+        setup_wrapped_sol_account(
+            &mut banks_client,
+            dst,
+            &Pubkey::new_unique(),
+            0u64,
+            &owner,
+            &recent_blockhash
+        ).await;
+    };
+
+    // 2. sweep the source by shares and close it to the owner
+    let ix = split_spl_tokens_from_single_mint_by_shares(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &NATIVE_MINT,
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        0u64,
+        vec![
+            1u64,
+            2u64
+        ],
+        true,
+        9u8,
+        TokenSplitOptions {
+            close_sources_to: Some(owner.pubkey()),
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let owner_balance = banks_client.get_balance(owner.pubkey()).await.unwrap();
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert!(
+        banks_client.get_account(src_token_account.pubkey()).await.unwrap().is_none(),
+        "Source token account was not closed."
+    );
+    assert!(
+        banks_client.get_balance(owner.pubkey()).await.unwrap() > owner_balance,
+        "Source token account rent was not reclaimed."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        sol_to_lamports(1.0),
+        "Destination token account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        sol_to_lamports(2.0),
+        "Destination second token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemintbyshares_close_destination_missing() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup the owner's wrapped SOL source and the recipients' native-mint token accounts
+    let owner = payer;
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_wrapped_sol_account(
+        &mut banks_client,
+        &src_token_account,
+        &owner.pubkey(),
+        sol_to_lamports(3.0),
+        &owner,
+        &recent_blockhash
+    ).await;
+    for dst in [&dst_token_account, &dst_sc_token_account] {
+        setup_wrapped_sol_account(
+            &mut banks_client,
+            dst,
+            &Pubkey::new_unique(),
+            0u64,
+            &owner,
+            &recent_blockhash
+        ).await;
+    };
+
+    // 2. sweep the source by shares and close it to another account
+    let close_destination = Pubkey::new_unique();
+    let mut ix = split_spl_tokens_from_single_mint_by_shares(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &NATIVE_MINT,
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        0u64,
+        vec![
+            1u64,
+            2u64
+        ],
+        true,
+        9u8,
+        TokenSplitOptions {
+            close_sources_to: Some(close_destination),
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+    // leave the close destination out
+    ix.accounts.retain(|account| account.pubkey != close_destination);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MissingCloseDestination
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_destination_not_rent_exempt() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. the first destination is a brand-new address receiving less than the rent-exempt minimum
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            1_000u64,
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions {
+            rent_exemption: RentExemptionPolicy::Fail,
+            ..LamportSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::DestinationNotRentExempt
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitlamports_rent_exemption_skip() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. the first destination is a brand-new address receiving less than the rent-exempt minimum
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            1_000u64,
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions {
+            rent_exemption: RentExemptionPolicy::Skip,
+            ..LamportSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        0u64,
+        "Destination account balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination second account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitlamports_rent_exemption_top_up() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. the first destination is a brand-new address receiving less than the rent-exempt minimum
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            1_000u64,
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions {
+            rent_exemption: RentExemptionPolicy::TopUp,
+            ..LamportSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        Rent::default().minimum_balance(0),
+        "Destination account balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination second account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_balance_guards() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split while the source holds at least 100 and keeps at least 70
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions {
+            guards: vec![
+                BalanceGuard {
+                    account: src_token_account.pubkey(),
+                    min_before: Some(100_00u64),
+                    min_after: Some(70_00u64)
+                }
+            ],
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        70_00u64,
+        "Source token-account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_starting_balance_below_guard() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. the source holds 100, less than the guard requires
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions {
+            guards: vec![
+                BalanceGuard {
+                    account: src_token_account.pubkey(),
+                    min_before: Some(100_01u64),
+                    min_after: Some(0u64)
+                }
+            ],
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::StartingBalanceBelowGuard
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_remaining_balance_below_guard() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. the source would keep 70, less than the guard requires
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions {
+            guards: vec![
+                BalanceGuard {
+                    account: src_token_account.pubkey(),
+                    min_before: Some(0u64),
+                    min_after: Some(70_01u64)
+                }
+            ],
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RemainingBalanceBelowGuard
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_remaining_balance_below_guard() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. the owner must keep more lamports than it holds
+    let owner = payer;
+    let owner_balance = banks_client.get_balance(owner.pubkey()).await.unwrap();
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            Pubkey::new_unique()
+        ],
+        vec![
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions {
+            guards: vec![
+                BalanceGuard {
+                    account: owner.pubkey(),
+                    min_before: None,
+                    min_after: Some(owner_balance)
+                }
+            ],
+            ..LamportSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RemainingBalanceBelowGuard
+    ).await;
+}
+
+#[tokio::test]
+async fn success_quotesplit() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let ix = quote_split(
+        &program_id,
+        10u64,
+        vec![
+            1u64,
+            2u64
+        ]
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .simulate_transaction(tx)
+        .await
+        .unwrap()
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    assert_eq!(
+        return_data.program_id,
+        program_id,
+        "Return data program mismatch."
+    );
+    assert_eq!(
+        SplitQuote::try_from_slice(&return_data.data).unwrap(),
+        SplitQuote {
+            amounts: vec![
+                3u64,
+                7u64
+            ],
+            total: 10u64,
+            fee: 0u64
+        },
+        "Quote mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_quotesplit_sweep() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. quote a sweep of the source in three equal shares
+    let ix = quote_split_sweep(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        vec![
+            1u64,
+            1u64,
+            1u64
+        ]
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .simulate_transaction(tx)
+        .await
+        .unwrap()
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    assert_eq!(
+        SplitQuote::try_from_slice(&return_data.data).unwrap(),
+        SplitQuote {
+            amounts: vec![
+                33_34u64,
+                33_33u64,
+                33_33u64
+            ],
+            total: 100_00u64,
+            fee: 0u64
+        },
+        "Quote mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &src_token_account.pubkey()).await.amount,
+        100_00u64,
+        "Source token-account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_quotesplit_protocol_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. charge 1% on every leg
+    let owner = payer;
+    let treasury = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        100u16,
+        &treasury,
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    // 2. quote the split, then run it
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+    let shares = vec![
+        1u64,
+        2u64
+    ];
+
+    let quote_ix = quote_split(
+        &program_id,
+        sol_to_lamports(3.0),
+        shares.clone()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[quote_ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .simulate_transaction(tx)
+        .await
+        .unwrap()
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap();
+    let quote = SplitQuote::try_from_slice(&return_data.data).unwrap();
+
+    assert_eq!(
+        quote,
+        SplitQuote {
+            amounts: vec![
+                sol_to_lamports(0.99),
+                sol_to_lamports(1.98)
+            ],
+            total: sol_to_lamports(3.0),
+            fee: sol_to_lamports(0.03)
+        },
+        "Quote mismatch."
+    );
+
+    let mut split_ix = split_lamports_by_shares(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        sol_to_lamports(3.0),
+        shares,
+        LamportSplitOptions::default()
+    ).unwrap();
+    split_ix.accounts.extend(treasury_account_metas(&treasury, &TOKEN_STANDARD_PROGRAM, &[]));
+
+    let tx = Transaction::new_signed_with_payer(
+        &[split_ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 3. recipients and treasury receive exactly what was quoted
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        quote.amounts[0],
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        quote.amounts[1],
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(treasury).await.unwrap(),
+        quote.fee,
+        "Treasury balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitlamports_summary() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    assert_eq!(
+        SplitSummary::unpack(&return_data.data).unwrap(),
+        SplitSummary {
+            kind: SplitKind::SplitLamports,
+            legs: 2u32,
+            totals: vec![
+                AssetTotal {
+                    mint: None,
+                    amount: sol_to_lamports(3.0)
+                }
+            ],
+            recipients_hash: hashv(&[dst_account.as_ref(), dst_sc_account.as_ref()]).to_bytes(),
+            fees: vec![]
+        },
+        "Summary mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_summary() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split and read the summary
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    assert_eq!(
+        return_data.program_id,
+        program_id,
+        "Return data program mismatch."
+    );
+    assert_eq!(
+        SplitSummary::unpack(&return_data.data).unwrap(),
+        SplitSummary {
+            kind: SplitKind::SplitSplTokensFromSingleMint,
+            legs: 2u32,
+            totals: vec![
+                AssetTotal {
+                    mint: Some(mint_account.pubkey()),
+                    amount: 30_00u64
+                }
+            ],
+            recipients_hash: hashv(
+                &[
+                    dst_token_account.pubkey().as_ref(),
+                    dst_sc_token_account.pubkey().as_ref()
+                ]
+            ).to_bytes(),
+            fees: vec![]
+        },
+        "Summary mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitterevent_from_logs() {
+    // builtin programs of `ProgramTest` print `sol_log_data` instead of logging it, so the
+    // events are packed here the way the program logs them
+    let dst_account = Pubkey::new_unique();
+    let events = vec![
+        SplitterEvent::SplitExecuted(
+            SplitExecuted {
+                kind: SplitKind::SplitLamports,
+                legs: 1u32,
+                totals: vec![
+                    AssetTotal {
+                        mint: None,
+                        amount: sol_to_lamports(1.0)
+                    }
+                ],
+                recipients_hash: hashv(&[dst_account.as_ref()]).to_bytes(),
+                fees: vec![]
+            }
+        ),
+        SplitterEvent::TransferLeg(
+            TransferLeg {
+                index: 0u32,
+                source: Pubkey::new_unique(),
+                destination: dst_account,
+                mint: None,
+                amount: sol_to_lamports(1.0)
+            }
+        )
+    ];
+
+    let mut log_messages: Vec<String> = vec![
+        "Program log: Instruction: SplitLamports".to_string(),
+        format!("Program data: {}", STANDARD.encode(b"not a splitter event"))
     ];
+    log_messages.extend(
+        events
+            .iter()
+            .map(|event| format!("Program data: {}", STANDARD.encode(event.pack().unwrap())))
+    );
+
+    assert_eq!(
+        SplitterEvent::from_logs(&log_messages),
+        events,
+        "Events mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_token_2022_required_memo() {
+    let program_id = Pubkey::new_unique();
+    let mut pt = setup(&program_id).await;
+    setup_memo_program(&mut pt);
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022, the first destination requires memos
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        None,
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_required_transfer_memos(
+        &mut banks_client,
+        &dst_token_account.pubkey(),
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split with a memo per leg
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_2022_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions {
+            memo: Some(
+                SplitMemo::PerLeg(
+                    vec![
+                        "INV-001".to_string(),
+                        String::new()
+                    ]
+                )
+            ),
+            ..TokenSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let log_messages = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .log_messages;
+
+    assert_eq!(
+        log_messages
+            .iter()
+            .filter(|log| log.starts_with("Program log: Memo"))
+            .collect::<Vec<&String>>(),
+        vec!["Program log: Memo (len 7): \"INV-001\""],
+        "Memo logs mismatch."
+    );
+    assert_eq!(
+        get_token_2022_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_token_2022_required_memo_missing() {
+    let program_id = Pubkey::new_unique();
+    let mut pt = setup(&program_id).await;
+    setup_memo_program(&mut pt);
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts under token-2022, the first destination requires memos
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint_token_2022(
+        &mut banks_client,
+        &mint_account,
+        &[
+            &src_token_account,
+            &dst_token_account,
+            &dst_sc_token_account
+        ],
+        None,
+        None,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_required_transfer_memos(
+        &mut banks_client,
+        &dst_token_account.pubkey(),
+        &owner,
+        &recent_blockhash
+    ).await;
+
+    // 2. split without a memo
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_2022_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let error = banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::NoMemo as u32)
+        ),
+        "Unexpected error"
+    );
+}
+
+#[tokio::test]
+async fn fail_splitlamports_memo_legs_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let mut pt = setup(&program_id).await;
+    setup_memo_program(&mut pt);
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let owner = payer;
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            Pubkey::new_unique(),
+            Pubkey::new_unique()
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions {
+            memo: Some(
+                SplitMemo::PerLeg(
+                    vec![
+                        "INV-001".to_string()
+                    ]
+                )
+            ),
+            ..LamportSplitOptions::default()
+        }
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MemoLegsMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn success_initializeconfig() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+    let treasury = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        25u16,
+        &treasury,
+        3_600u32,
+        &recent_blockhash
+    ).await;
+
+    let (config_address, bump) = get_config_address(&program_id);
+    let config_account = banks_client
+        .get_account(config_address)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        config_account.owner,
+        program_id,
+        "Config owner mismatch."
+    );
+    assert_eq!(
+        Config::unpack(&config_account.data).unwrap(),
+        Config {
+            admin: admin.pubkey(),
+            fee_basis_points: 25u16,
+            treasury,
+            paused: false,
+            bump,
+            pending_admin: None,
+            timelock_delay: 3_600u32,
+            next_change_id: 0u64,
+            pending_changes: vec![]
+        },
+        "Config mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_initializeconfig_funded_address() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+    let treasury = Pubkey::new_unique();
+    let config_address = get_config_address(&program_id).0;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            transfer_lamports(
+                &admin.pubkey(),
+                &config_address,
+                Rent::default().minimum_balance(0)
+            )
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        25u16,
+        &treasury,
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let config_account = banks_client
+        .get_account(config_address)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        config_account.owner,
+        program_id,
+        "Config owner mismatch."
+    );
+    assert_eq!(
+        config_account.lamports,
+        Rent::default().minimum_balance(Config::LEN),
+        "Config lamports mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_initializeconfig_invalid_upgrade_authority() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let ix = initialize_config(
+        &program_id,
+        &payer.pubkey(),
+        &payer.pubkey(),
+        &payer.pubkey(),
+        25u16,
+        &Pubkey::new_unique(),
+        0u32
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidUpgradeAuthority
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_initializeconfig_invalid_program_data() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let upgrade_authority = upgrade_authority();
+    let mut ix = initialize_config(
+        &program_id,
+        &payer.pubkey(),
+        &upgrade_authority.pubkey(),
+        &payer.pubkey(),
+        25u16,
+        &Pubkey::new_unique(),
+        0u32
+    ).unwrap();
+    // program data of another program
+    ix.accounts[2].pubkey = get_program_data_address(&Pubkey::new_unique());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, &upgrade_authority],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidProgramData
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_initializeconfig_already_initialized() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        25u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    // the upgrade authority can not initialize the config a second time
+    let upgrade_authority = upgrade_authority();
+    let ix = initialize_config(
+        &program_id,
+        &admin.pubkey(),
+        &upgrade_authority.pubkey(),
+        &admin.pubkey(),
+        50u16,
+        &Pubkey::new_unique(),
+        0u32
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[&admin, &upgrade_authority],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::ConfigAlreadyInitialized
+    ).await;
+}
+
+#[tokio::test]
+async fn success_initializeconfig_max_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        MAX_FEE_BASIS_POINTS,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let config_account = banks_client
+        .get_account(get_config_address(&program_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        Config::unpack(&config_account.data).unwrap().fee_basis_points,
+        MAX_FEE_BASIS_POINTS,
+        "Fee mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_initializeconfig_invalid_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let upgrade_authority = upgrade_authority();
+    let ix = initialize_config(
+        &program_id,
+        &payer.pubkey(),
+        &upgrade_authority.pubkey(),
+        &payer.pubkey(),
+        MAX_FEE_BASIS_POINTS + 1,
+        &Pubkey::new_unique(),
+        0u32
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, &upgrade_authority],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidFee
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitlamports_protocol_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. charge 1% on every leg
+    let owner = payer;
+    let treasury = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        100u16,
+        &treasury,
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    // 2. split with the treasury appended
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let mut ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+    ix.accounts.extend(treasury_account_metas(&treasury, &TOKEN_STANDARD_PROGRAM, &[]));
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 3. recipients receive their amount net of the fee
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        sol_to_lamports(0.99),
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(1.98),
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(treasury).await.unwrap(),
+        sol_to_lamports(0.03),
+        "Treasury balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitlamports_protocol_fee_summary() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. charge 1% on every leg
+    let owner = payer;
+    let treasury = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        100u16,
+        &treasury,
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    // 2. split with the treasury appended
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    let mut ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+    ix.accounts.extend(treasury_account_metas(&treasury, &TOKEN_STANDARD_PROGRAM, &[]));
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    let return_data = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    // 3. the fee transfer is reported apart from the requested legs
+    assert_eq!(
+        SplitSummary::unpack(&return_data.data).unwrap(),
+        SplitSummary {
+            kind: SplitKind::SplitLamports,
+            legs: 2u32,
+            totals: vec![
+                AssetTotal {
+                    mint: None,
+                    amount: sol_to_lamports(2.97)
+                }
+            ],
+            recipients_hash: hashv(&[dst_account.as_ref(), dst_sc_account.as_ref()]).to_bytes(),
+            fees: vec![
+                AssetTotal {
+                    mint: None,
+                    amount: sol_to_lamports(0.03)
+                }
+            ]
+        },
+        "Summary mismatch."
+    );
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_protocol_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, charge 10% on every leg
+    let owner = payer;
+    let treasury = Pubkey::new_unique();
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        1_000u16,
+        &treasury,
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_associated_token_account_idempotent(
+                &owner.pubkey(),
+                &treasury,
+                &mint_account.pubkey(),
+                &TOKEN_STANDARD_PROGRAM
+            )
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. split with the treasury and its token account appended
+    let mut ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+    ix.accounts.extend(
+        treasury_account_metas(
+            &treasury,
+            &TOKEN_STANDARD_PROGRAM,
+            &[mint_account.pubkey()]
+        )
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 3. check balances
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        9_00u64,
+        "Destination token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        18_00u64,
+        "Destination token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(
+            &mut banks_client,
+            &get_associated_token_address(&treasury, &mint_account.pubkey())
+        ).await.amount,
+        3_00u64,
+        "Treasury token-account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitlamports_missing_treasury() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let owner = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        100u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            Pubkey::new_unique()
+        ],
+        vec![
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MissingTreasuryAccount
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_paused() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. pause splits
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            set_paused(&program_id, &admin.pubkey(), true).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. any split fails while paused
+    let ix = split_lamports(
+        &program_id,
+        &admin.pubkey(),
+        &[
+            Pubkey::new_unique()
+        ],
+        vec![
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::SplitsPaused
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_queueconfigchange_invalid_admin() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+    let impostor = Keypair::new();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            queue_config_change(
+                &program_id,
+                &impostor.pubkey(),
+                ConfigChange::Treasury(impostor.pubkey())
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin, &impostor],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidAdmin
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_queueconfigchange_invalid_fee() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            queue_config_change(
+                &program_id,
+                &admin.pubkey(),
+                ConfigChange::Fee(MAX_FEE_BASIS_POINTS + 1)
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidFee
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_queueconfigchange_too_many_pending_changes() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        3600u32,
+        &recent_blockhash
+    ).await;
+
+    // 1. fill the queue
+    let ixs = (0..MAX_PENDING_CHANGES)
+        .map(|i| queue_config_change(
+            &program_id,
+            &admin.pubkey(),
+            ConfigChange::Fee(i as u16)
+        ).unwrap())
+        .collect::<Vec<Instruction>>();
+
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. one more change does not fit
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            queue_config_change(
+                &program_id,
+                &admin.pubkey(),
+                ConfigChange::Fee(MAX_FEE_BASIS_POINTS)
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::TooManyPendingChanges
+    ).await;
+}
+
+#[tokio::test]
+async fn success_proposeadmin_acceptadmin() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. the admin proposes its successor
+    let admin = payer;
+    let new_admin = Keypair::new();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            propose_admin(&program_id, &admin.pubkey(), Some(new_admin.pubkey())).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let config = get_config(&mut banks_client, &program_id).await;
+    assert_eq!(
+        config.admin,
+        admin.pubkey(),
+        "Admin changed before the handover was accepted."
+    );
+    assert_eq!(
+        config.pending_admin,
+        Some(new_admin.pubkey()),
+        "Pending admin mismatch."
+    );
+
+    // 2. the successor accepts the handover
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            accept_admin(&program_id, &new_admin.pubkey()).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin, &new_admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let config = get_config(&mut banks_client, &program_id).await;
+    assert_eq!(
+        config.admin,
+        new_admin.pubkey(),
+        "Admin mismatch."
+    );
+    assert_eq!(
+        config.pending_admin,
+        None,
+        "Pending admin not cleared."
+    );
+}
+
+#[tokio::test]
+async fn fail_acceptadmin_invalid_pending_admin() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+    let new_admin = Keypair::new();
+    let impostor = Keypair::new();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            propose_admin(&program_id, &admin.pubkey(), Some(new_admin.pubkey())).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            accept_admin(&program_id, &impostor.pubkey()).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin, &impostor],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPendingAdmin
+    ).await;
+}
+
+#[tokio::test]
+async fn success_executeconfigchange_after_timelock() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let mut context = pt.start_with_context().await;
+
+    // 1. queue a new treasury behind a one-hour timelock
+    let admin = context.payer.insecure_clone();
+    let treasury = Pubkey::new_unique();
+    let recent_blockhash = context.last_blockhash;
+
+    setup_config(
+        &mut context.banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        3_600u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            queue_config_change(
+                &program_id,
+                &admin.pubkey(),
+                ConfigChange::Treasury(treasury)
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let clock = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap();
+    assert_eq!(
+        get_config(&mut context.banks_client, &program_id).await.pending_changes,
+        vec![
+            PendingChange {
+                id: 0u64,
+                change: ConfigChange::Treasury(treasury),
+                executable_at: clock.unix_timestamp + 3_600i64
+            }
+        ],
+        "Pending changes mismatch."
+    );
+
+    // 2. the change can not be executed before the timelock elapses
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            execute_config_change(&program_id, &admin.pubkey(), 0u64).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut context.banks_client,
+        tx,
+        SplitterError::TimelockNotElapsed
+    ).await;
+
+    // 3. execute it once the timelock has elapsed
+    context.set_sysvar(
+        &Clock {
+            unix_timestamp: clock.unix_timestamp + 3_600i64,
+            ..clock
+        }
+    );
+    let recent_blockhash = context
+        .get_new_latest_blockhash()
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            execute_config_change(&program_id, &admin.pubkey(), 0u64).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let config = get_config(&mut context.banks_client, &program_id).await;
+    assert_eq!(
+        config.treasury,
+        treasury,
+        "Treasury mismatch."
+    );
+    assert!(
+        config.pending_changes.is_empty(),
+        "Executed change still pending."
+    );
+}
+
+#[tokio::test]
+async fn success_cancelconfigchange() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. queue two fee changes and cancel the first one
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            queue_config_change(&program_id, &admin.pubkey(), ConfigChange::Fee(500u16)).unwrap(),
+            queue_config_change(&program_id, &admin.pubkey(), ConfigChange::Fee(100u16)).unwrap(),
+            cancel_config_change(&program_id, &admin.pubkey(), 0u64).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let config = get_config(&mut banks_client, &program_id).await;
+    assert_eq!(
+        config
+            .pending_changes
+            .iter()
+            .map(|pending_change| pending_change.id)
+            .collect::<Vec<u64>>(),
+        vec![1u64],
+        "Pending changes mismatch."
+    );
+
+    // 2. the canceled change can not be executed anymore
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            execute_config_change(&program_id, &admin.pubkey(), 0u64).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PendingChangeNotFound
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_splitlamports_recipient_denied() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. deny the second recipient
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::RecipientDenylist,
+        vec![dst_sc_account],
+        &recent_blockhash
+    ).await;
+
+    // 2. the whole split is rejected
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RecipientDenied
+    ).await;
+}
+
+#[tokio::test]
+async fn success_addpolicyaddresses_long_denylist() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. deny a hundred addresses, the second recipient among them, over several instructions
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
+    let mut denied = (0..99)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<Pubkey>>();
+    denied.insert(42, dst_sc_account);
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::RecipientDenylist,
+        vec![],
+        &recent_blockhash
+    ).await;
+
+    for addresses in denied.chunks(25) {
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                add_policy_addresses(
+                    &program_id,
+                    &owner.pubkey(),
+                    &owner.pubkey(),
+                    PolicyKind::RecipientDenylist,
+                    addresses.to_vec()
+                ).unwrap()
+            ],
+            Some(&owner.pubkey()),
+            &[&owner],
+            recent_blockhash
+        );
+
+        banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap();
+    };
+
+    let policy_account = banks_client
+        .get_account(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0)
+        .await
+        .unwrap()
+        .unwrap();
+    denied.sort();
+
+    assert_eq!(
+        policy_account.data.len(),
+        Policy::space(100usize),
+        "Policy account length mismatch."
+    );
+    assert_eq!(
+        Policy::unpack(&policy_account.data).unwrap().addresses,
+        denied,
+        "Policy addresses mismatch."
+    );
+
+    // 2. the split to the denied recipient is rejected
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account,
+            dst_sc_account
+        ],
+        vec![
+            sol_to_lamports(1.0),
+            sol_to_lamports(2.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RecipientDenied
+    ).await;
+
+    // 3. once unlisted, the same split goes through
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            remove_policy_addresses(
+                &program_id,
+                &owner.pubkey(),
+                PolicyKind::RecipientDenylist,
+                vec![dst_sc_account]
+            ).unwrap(),
+            ix
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        banks_client
+            .get_account(get_policy_address(&program_id, PolicyKind::RecipientDenylist).0)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .len(),
+        Policy::space(99usize),
+        "Policy account length mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(2.0),
+        "Destination balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_recipient_owner_denied() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, deny the owner of the second destination
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    let denied_owner = get_token_account(&mut banks_client, &dst_sc_token_account.pubkey())
+        .await
+        .owner;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::RecipientDenylist,
+        vec![denied_owner],
+        &recent_blockhash
+    ).await;
+
+    // 2. the whole split is rejected
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RecipientDenied
+    ).await;
+}
+
+#[tokio::test]
+async fn success_splitfromsinglemint_mint_allowlist() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, allow only the split mint
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::MintAllowlist,
+        vec![
+            Pubkey::new_unique(),
+            mint_account.pubkey()
+        ],
+        &recent_blockhash
+    ).await;
+
+    // 2. split
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
         &[
-            Instruction {
-                program_id,
-                data,
-                accounts: keys
-            }
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
         ],
-        Some(&operator.pubkey()),
-        &[&operator],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
         recent_blockhash
     );
 
-    let operator_balance_before = banks_client.get_balance(operator.pubkey())
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 3. check balances
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
+        10_00u64,
+        "Destination token-account balance mismatch."
+    );
+    assert_eq!(
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
+        20_00u64,
+        "Destination token-account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_splitfromsinglemint_mint_not_allowed() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. setup all mint and token-accounts, allow another mint only
+    let owner = payer;
+    let mint_account = Keypair::new();
+    let src_token_account = Keypair::new();
+    let dst_token_account = Keypair::new();
+    let dst_sc_token_account = Keypair::new();
+
+    setup_single_mint(
+        &mut banks_client,
+        &mint_account,
+        &src_token_account,
+        &dst_token_account,
+        &dst_sc_token_account,
+        &owner,
+        &recent_blockhash
+    ).await;
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::MintAllowlist,
+        vec![Pubkey::new_unique()],
+        &recent_blockhash
+    ).await;
+
+    // 2. the split is rejected
+    let ix = split_spl_tokens_from_single_mint(
+        &program_id,
+        &TOKEN_STANDARD_PROGRAM,
+        &owner.pubkey(),
+        &[],
+        &mint_account.pubkey(),
+        &src_token_account.pubkey(),
+        &[
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        vec![
+            10_00u64,
+            20_00u64
+        ],
+        2u8,
+        TokenSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::MintNotAllowed
+    ).await;
+}
+
+#[tokio::test]
+async fn success_closepolicy() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. deny the recipient, then close the denylist
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PolicyKind::RecipientDenylist,
+        vec![dst_account],
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            close_policy(
+                &program_id,
+                &owner.pubkey(),
+                PolicyKind::RecipientDenylist,
+                &owner.pubkey()
+            ).unwrap()
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    // 2. the recipient can be paid again
+    let ix = split_lamports(
+        &program_id,
+        &owner.pubkey(),
+        &[
+            dst_account
+        ],
+        vec![
+            sol_to_lamports(1.0)
+        ],
+        LamportSplitOptions::default()
+    ).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    banks_client
+        .process_transaction(tx)
         .await
         .unwrap();
 
-    banks_client
-        .process_transaction(tx)
-        .await
-        .unwrap();
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_addpolicyaddresses_invalid_admin() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+    let impostor = Keypair::new();
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+    setup_policy(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        PolicyKind::RecipientDenylist,
+        vec![],
+        &recent_blockhash
+    ).await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            add_policy_addresses(
+                &program_id,
+                &admin.pubkey(),
+                &impostor.pubkey(),
+                PolicyKind::RecipientDenylist,
+                vec![admin.pubkey()]
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin, &impostor],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidAdmin
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_initializepolicy_invalid_policy() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
+
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
+
+    // the denylist PDA given for the allowlist
+    let mut ix = initialize_policy(
+        &program_id,
+        &admin.pubkey(),
+        &admin.pubkey(),
+        PolicyKind::MintAllowlist
+    ).unwrap();
+    ix.accounts[3].pubkey = get_policy_address(&program_id, PolicyKind::RecipientDenylist).0;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPolicy
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_addpolicyaddresses_invalid_policy() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let admin = payer;
 
-    let account_1 = banks_client.get_account(*accounts.get(0usize).unwrap())
-        .await
-        .unwrap()
-        .unwrap();
-    let account_2 = banks_client.get_account(*accounts.get(1usize).unwrap())
-        .await
-        .unwrap()
-        .unwrap();
-    let account_3 = banks_client.get_account(*accounts.get(2usize).unwrap())
-        .await
-        .unwrap()
-        .unwrap();
+    setup_config(
+        &mut banks_client,
+        &program_id,
+        &admin,
+        0u16,
+        &Pubkey::new_unique(),
+        0u32,
+        &recent_blockhash
+    ).await;
 
-    assert_eq!(
-        account_1.lamports,
-        sol_to_lamports(1.1),
-        "Mismacth account_1 balance"
-    );
-    assert_eq!(
-        account_2.lamports,
-        sol_to_lamports(1.2),
-        "Mismacth account_2 balance"
-    );
-    assert_eq!(
-        account_3.lamports,
-        sol_to_lamports(1.3),
-        "Mismacth account_3 balance"
+    // the policy is not initialized
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            add_policy_addresses(
+                &program_id,
+                &admin.pubkey(),
+                &admin.pubkey(),
+                PolicyKind::RecipientDenylist,
+                vec![Pubkey::new_unique()]
+            ).unwrap()
+        ],
+        Some(&admin.pubkey()),
+        &[&admin],
+        recent_blockhash
     );
 
-    let operator_balance_after = banks_client.get_balance(operator.pubkey())
-        .await
-        .unwrap();
-    assert_eq!(
-        operator_balance_before - (sol_to_lamports(3.6) + 5000),
-        operator_balance_after,
-        "Mismatch operator balance"
-    );
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPolicy
+    ).await;
 }
 
 #[tokio::test]
-async fn fail_splitlamports() {
+async fn success_executeplan_lamports_by_shares() {
     let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
         mut banks_client,
-        operator,
+        payer,
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. data
-    let amounts: Vec<u64> = vec![
-        sol_to_lamports(1.1),
-        sol_to_lamports(1.2),
-        sol_to_lamports(1.3)
-    ];
-    let mut data: Vec<u8> = vec![];
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitlamports").as_slice()
-    );
-    data.append(
-        &mut SplitLamports { amounts }
-        .try_to_vec()
-        .unwrap()
-    );
+    // 1. store the recipients and their shares
+    let owner = payer;
+    let dst_account = Pubkey::new_unique();
+    let dst_sc_account = Pubkey::new_unique();
 
-    // 2. provide accounts
-    let accounts: Vec<Pubkey> = vec![
-        Keypair::new().pubkey(),
-        Keypair::new().pubkey()
-    ]; 
-    let keys: Vec<AccountMeta> = vec![
-        AccountMeta::new(operator.pubkey(), true),
-        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-        AccountMeta::new(*accounts.get(0_usize).unwrap(), false),
-        AccountMeta::new(*accounts.get(1_usize).unwrap(), false)
-    ];
+    let plan = setup_plan(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PlanAsset::Lamports,
+        vec![
+            dst_account,
+            dst_sc_account
+        ],
+        PlanAllocation::Shares(vec![1u64, 3u64]),
+        &recent_blockhash
+    ).await;
 
+    // 2. run the plan
     let tx = Transaction::new_signed_with_payer(
         &[
-            Instruction {
-                program_id,
-                data,
-                accounts: keys
-            }
+            execute_lamports_plan(
+                &program_id,
+                &owner.pubkey(),
+                &plan,
+                sol_to_lamports(4.0),
+                LamportSplitOptions::default()
+            ).unwrap()
         ],
-        Some(&operator.pubkey()),
-        &[&operator],
+        Some(&owner.pubkey()),
+        &[&owner],
         recent_blockhash
     );
 
-    banks_client
-        .process_transaction(tx)
+    let return_data = banks_client
+        .process_transaction_with_metadata(tx)
         .await
-        .unwrap_err();
+        .unwrap()
+        .metadata
+        .unwrap()
+        .return_data
+        .unwrap();
+
+    // 3. check balances and summary
+    assert_eq!(
+        banks_client.get_balance(dst_account).await.unwrap(),
+        sol_to_lamports(1.0),
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        banks_client.get_balance(dst_sc_account).await.unwrap(),
+        sol_to_lamports(3.0),
+        "Destination balance mismatch."
+    );
+    assert_eq!(
+        SplitSummary::unpack(&return_data.data).unwrap().kind,
+        SplitKind::ExecutePlan,
+        "Summary kind mismatch."
+    );
 }
 
 #[tokio::test]
-async fn success_splitfromsinglemint() {
+async fn success_executeplan_token_amounts() {
     let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
@@ -611,7 +7923,7 @@ async fn success_splitfromsinglemint() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
+    // 1. setup all mint and token-accounts, store the fixed amounts
     let owner = payer;
     let mint_account = Keypair::new();
     let src_token_account = Keypair::new();
@@ -628,40 +7940,35 @@ async fn success_splitfromsinglemint() {
         &recent_blockhash
     ).await;
 
-    // test owner instruction
-    // 2. provide data
-    let amounts: Vec<u64> = vec![
-        10_00u64,
-        20_00u64
-    ];
-
-    let mut data: Vec<u8> = vec![];
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
-    );
-    data.append(
-        &mut SplitSplTokensFromSingleMint { amounts }
-            .try_to_vec()
-            .unwrap()
-    );
-
-    // 3. provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account.pubkey(), false),
-        AccountMeta::new(dst_token_account.pubkey(), false),
-        AccountMeta::new(dst_sc_token_account.pubkey(), false)
-    ];
+    let plan = setup_plan(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PlanAsset::Token {
+            mint: mint_account.pubkey(),
+            decimals: 2u8
+        },
+        vec![
+            dst_token_account.pubkey(),
+            dst_sc_token_account.pubkey()
+        ],
+        PlanAllocation::Amounts(vec![10_00u64, 20_00u64]),
+        &recent_blockhash
+    ).await;
 
-    // 4. send transaction
+    // 2. run the plan
     let tx = Transaction::new_signed_with_payer(
         &[
-            Instruction {
-                program_id,
-                data,
-                accounts
-            }
+            execute_token_plan(
+                &program_id,
+                &TOKEN_STANDARD_PROGRAM,
+                &owner.pubkey(),
+                &[],
+                &src_token_account.pubkey(),
+                &plan,
+                0u64,
+                TokenSplitOptions::default()
+            ).unwrap()
         ],
         Some(&owner.pubkey()),
         &[&owner],
@@ -673,61 +7980,187 @@ async fn success_splitfromsinglemint() {
         .await
         .unwrap();
 
-    // Checks...
-    let mint_account_info = banks_client.get_account(mint_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-
-    let mint_account = MintAccount::unpack(
-        mint_account_info.data.as_slice()
-    ).unwrap();
-    let source_token_account = TokenAccount::unpack(
-        source_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_token_account = TokenAccount::unpack(
-        destination_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_second_token_account = TokenAccount::unpack(
-        destination_second_token_account_info.data.as_slice()
-    ).unwrap();
-
-    assert_eq!(
-        mint_account.supply,
-        100_00u64,
-        "Mint's supply mismatch."
-    );
+    // 3. check balances
     assert_eq!(
-        source_token_account.amount,
-        70_00u64,
-        "Source token-account balance mismatch."
-    );
-    assert_eq!(
-        destination_token_account.amount,
+        get_token_account(&mut banks_client, &dst_token_account.pubkey()).await.amount,
         10_00u64,
-        "Destination token account balance mismatch."
+        "Destination token-account balance mismatch."
     );
     assert_eq!(
-        destination_second_token_account.amount,
+        get_token_account(&mut banks_client, &dst_sc_token_account.pubkey()).await.amount,
         20_00u64,
-        "Destination second token account balance mismatch."
+        "Destination token-account balance mismatch."
+    );
+}
+
+#[tokio::test]
+async fn fail_executeplan_destination_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let owner = payer;
+
+    let plan = setup_plan(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PlanAsset::Lamports,
+        vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique()
+        ],
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0), sol_to_lamports(2.0)]),
+        &recent_blockhash
+    ).await;
+
+    // pass other destinations than the stored recipients
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            execute_lamports_plan(
+                &program_id,
+                &owner.pubkey(),
+                &SplitPlan {
+                    recipients: vec![
+                        plan.recipients[0],
+                        Pubkey::new_unique()
+                    ],
+                    ..plan.clone()
+                },
+                0u64,
+                LamportSplitOptions::default()
+            ).unwrap()
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PlanDestinationMismatch
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_executeplan_remaining_balance_below_guard() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. store the recipient
+    let owner = payer;
+
+    let plan = setup_plan(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PlanAsset::Lamports,
+        vec![
+            Pubkey::new_unique()
+        ],
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0)]),
+        &recent_blockhash
+    ).await;
+
+    // 2. the guard of the options applies to the split the plan runs
+    let owner_balance = banks_client.get_balance(owner.pubkey()).await.unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            execute_lamports_plan(
+                &program_id,
+                &owner.pubkey(),
+                &plan,
+                0u64,
+                LamportSplitOptions {
+                    guards: vec![
+                        BalanceGuard {
+                            account: owner.pubkey(),
+                            min_before: None,
+                            min_after: Some(owner_balance)
+                        }
+                    ],
+                    ..LamportSplitOptions::default()
+                }
+            ).unwrap()
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::RemainingBalanceBelowGuard
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_executeplan_options_mismatch() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    // 1. store the recipient
+    let owner = payer;
+
+    let plan = setup_plan(
+        &mut banks_client,
+        &program_id,
+        &owner,
+        PlanAsset::Lamports,
+        vec![
+            Pubkey::new_unique()
+        ],
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0)]),
+        &recent_blockhash
+    ).await;
+
+    // 2. token options are given to a plan of lamports
+    let mut ix = execute_lamports_plan(
+        &program_id,
+        &owner.pubkey(),
+        &plan,
+        0u64,
+        LamportSplitOptions::default()
+    ).unwrap();
+    ix.data = SplitterInstruction::ExecutePlan(
+        0u64,
+        plan.version,
+        PlanSplitOptions::Token(TokenSplitOptions::default())
+    ).pack().unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
     );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PlanOptionsMismatch
+    ).await;
 }
 
 #[tokio::test]
-async fn success_splitfromsinglemint_2() {
+async fn success_updateplan_closeplan() {
     let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
@@ -736,59 +8169,69 @@ async fn success_splitfromsinglemint_2() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
-    let owner = payer;
-    let mint_account = Keypair::new();
-    let src_token_account = Keypair::new();
-    let dst_token_account = Keypair::new();
-    let dst_sc_token_account = Keypair::new();
+    // 1. replace the recipients of the plan
+    let authority = payer;
+    let recipient = Pubkey::new_unique();
 
-    setup_single_mint(
+    setup_plan(
         &mut banks_client,
-        &mint_account,
-        &src_token_account,
-        &dst_token_account,
-        &dst_sc_token_account,
-        &owner,
+        &program_id,
+        &authority,
+        PlanAsset::Lamports,
+        vec![Pubkey::new_unique()],
+        PlanAllocation::Shares(vec![1u64]),
         &recent_blockhash
     ).await;
 
-    // test owner instruction
-    // 2. provide data
-    let amounts: Vec<u64> = vec![
-        50_00u64
-    ];
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            update_plan(
+                &program_id,
+                &authority.pubkey(),
+                0u64,
+                vec![recipient],
+                PlanAllocation::Amounts(vec![sol_to_lamports(1.0)])
+            ).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
+    );
 
-    let mut data: Vec<u8> = vec![];
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let plan = get_plan(&mut banks_client, &program_id, &authority.pubkey()).await;
+    assert_eq!(
+        plan.recipients,
+        vec![recipient],
+        "Recipients mismatch."
     );
-    data.append(
-        &mut SplitSplTokensFromSingleMint { amounts }
-            .try_to_vec()
-            .unwrap()
+    assert_eq!(
+        plan.allocation,
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0)]),
+        "Allocation mismatch."
+    );
+    assert_eq!(
+        plan.version,
+        1u64,
+        "Version mismatch."
     );
 
-    // 3. provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account.pubkey(), false),
-        AccountMeta::new(dst_token_account.pubkey(), false),
-        AccountMeta::new(dst_sc_token_account.pubkey(), false)
-    ];
-
-    // 4. send transaction
+    // 2. close it
     let tx = Transaction::new_signed_with_payer(
         &[
-            Instruction {
-                program_id,
-                data,
-                accounts
-            }
+            close_plan(
+                &program_id,
+                &authority.pubkey(),
+                0u64,
+                &authority.pubkey()
+            ).unwrap()
         ],
-        Some(&owner.pubkey()),
-        &[&owner],
+        Some(&authority.pubkey()),
+        &[&authority],
         recent_blockhash
     );
 
@@ -797,61 +8240,28 @@ async fn success_splitfromsinglemint_2() {
         .await
         .unwrap();
 
-    // Checks...
-    let mint_account_info = banks_client.get_account(mint_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
+    let plan_account = banks_client
+        .get_account(get_plan_address(&program_id, &authority.pubkey(), 0u64).0)
         .await
         .unwrap()
         .unwrap();
 
-    let mint_account = MintAccount::unpack(
-        mint_account_info.data.as_slice()
-    ).unwrap();
-    let source_token_account = TokenAccount::unpack(
-        source_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_token_account = TokenAccount::unpack(
-        destination_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_second_token_account = TokenAccount::unpack(
-        destination_second_token_account_info.data.as_slice()
-    ).unwrap();
-
-    assert_eq!(
-        mint_account.supply,
-        100_00u64,
-        "Mint's supply mismatch."
-    );
-    assert_eq!(
-        source_token_account.amount,
-        50_00u64,
-        "Source token-account balance mismatch."
-    );
     assert_eq!(
-        destination_token_account.amount,
-        50_00u64,
-        "Destination token account balance mismatch."
+        PlanTombstone::unpack(&plan_account.data).unwrap(),
+        PlanTombstone {
+            version: 1u64
+        },
+        "Plan tombstone mismatch."
     );
     assert_eq!(
-        destination_second_token_account.amount,
-        0_00u64,
-        "Destination second token account balance mismatch."
+        plan_account.lamports,
+        Rent::default().minimum_balance(PlanTombstone::LEN),
+        "Plan tombstone lamports mismatch."
     );
 }
 
 #[tokio::test]
-async fn fail_splitfromsinglemint() {
+async fn fail_executeplan_closed_and_created_again() {
     let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
@@ -860,123 +8270,81 @@ async fn fail_splitfromsinglemint() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
-    let owner = payer;
-    let mint_account = Keypair::new();
-    let src_token_account = Keypair::new();
-    let dst_token_account = Keypair::new();
-    let dst_sc_token_account = Keypair::new();
+    // 1. sign an execution of the plan at version 0
+    let authority = payer;
+    let recipient = Pubkey::new_unique();
 
-    setup_single_mint(
+    let plan = setup_plan(
         &mut banks_client,
-        &mint_account,
-        &src_token_account,
-        &dst_token_account,
-        &dst_sc_token_account,
-        &owner,
+        &program_id,
+        &authority,
+        PlanAsset::Lamports,
+        vec![recipient],
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0)]),
         &recent_blockhash
     ).await;
 
-    // test owner instruction
-    // 2. provide data
-    let amounts: Vec<u64> = vec![
-        10_00u64,
-        20_00u64
-    ];
-
-    let mut data: Vec<u8> = vec![];
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfromsinglemint").as_slice()
-    );
-    data.append(
-        &mut SplitSplTokensFromSingleMint { amounts }
-            .try_to_vec()
-            .unwrap()
-    );
-
-    // 3. provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account.pubkey(), false),
-        AccountMeta::new(dst_token_account.pubkey(), false)
-    ];
+    let stale_ix = execute_lamports_plan(
+        &program_id,
+        &authority.pubkey(),
+        &plan,
+        0u64,
+        LamportSplitOptions::default()
+    ).unwrap();
 
-    // 4. send transaction
+    // 2. close the plan and create it again with a larger amount
     let tx = Transaction::new_signed_with_payer(
         &[
-            Instruction {
-                program_id,
-                data,
-                accounts
-            }
+            close_plan(
+                &program_id,
+                &authority.pubkey(),
+                0u64,
+                &authority.pubkey()
+            ).unwrap(),
+            create_plan(
+                &program_id,
+                &authority.pubkey(),
+                &authority.pubkey(),
+                0u64,
+                PlanAsset::Lamports,
+                vec![recipient],
+                PlanAllocation::Amounts(vec![sol_to_lamports(5.0)])
+            ).unwrap()
         ],
-        Some(&owner.pubkey()),
-        &[&owner],
+        Some(&authority.pubkey()),
+        &[&authority],
         recent_blockhash
     );
 
     banks_client
         .process_transaction(tx)
         .await
-        .unwrap_err();
-
-    // Checks...
-    let mint_account_info = banks_client.get_account(mint_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let source_token_account_info = banks_client.get_account(src_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_token_account_info = banks_client.get_account(dst_token_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let destination_second_token_account_info = banks_client.get_account(dst_sc_token_account.pubkey())
-        .await
-        .unwrap()
         .unwrap();
 
-    let mint_account = MintAccount::unpack(
-        mint_account_info.data.as_slice()
-    ).unwrap();
-    let source_token_account = TokenAccount::unpack(
-        source_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_token_account = TokenAccount::unpack(
-        destination_token_account_info.data.as_slice()
-    ).unwrap();
-    let destination_second_token_account = TokenAccount::unpack(
-        destination_second_token_account_info.data.as_slice()
-    ).unwrap();
-
     assert_eq!(
-        mint_account.supply,
-        100_00u64,
-        "Mint's supply mismatch."
-    );
-    assert_eq!(
-        source_token_account.amount,
-        100_00u64,
-        "Source token-account balance mismatch."
+        get_plan(&mut banks_client, &program_id, &authority.pubkey()).await.version,
+        1u64,
+        "Version mismatch."
     );
-    assert_eq!(
-        destination_token_account.amount,
-        0_00u64,
-        "Destination token account balance mismatch."
-    );
-    assert_eq!(
-        destination_second_token_account.amount,
-        0_00u64,
-        "Destination second token account balance mismatch."
+
+    // 3. the execution signed for the closed plan does not run the new one
+    let tx = Transaction::new_signed_with_payer(
+        &[stale_ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
     );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PlanVersionMismatch
+    ).await;
 }
 
 #[tokio::test]
-async fn success_splitefrommultiplemintaccounts() {
-    let program_id = Pubkey::new_from_array([5u8; 32]);
+async fn fail_updateplan_invalid_authority() {
+    let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
         mut banks_client,
@@ -984,136 +8352,123 @@ async fn success_splitefrommultiplemintaccounts() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
-    let owner = payer;
-    let mint_account_1 = Keypair::new();
-    let mint_account_2 = Keypair::new();
-    let src_token_account_mint_1 = Keypair::new();
-    let src_token_account_mint_2 = Keypair::new();
-    let dst_token_account_mint_1 = Keypair::new();
-    let dst_token_account_mint_2 = Keypair::new();
-    
-    setup_multiple_mints(
-        &mut banks_client,
-        &mint_account_1,
-        &mint_account_2,
-        &src_token_account_mint_1,
-        &src_token_account_mint_2,
-        &dst_token_account_mint_1,
-        &dst_token_account_mint_2,
-        &owner,
-        &recent_blockhash
-    ).await;
-
-    // 2. Create instruction
-    // provide data
-    let mut data: Vec<u8> = Vec::new();
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
-    );
-
-    let amounts: Vec<u64> = vec![
-        100_000u64,
-        50_00u64
-    ];
-    let m = 2u16;
+    let authority = payer;
+    let impostor = Keypair::new();
 
-    data.append(
-        &mut SplitSplTokensFromMultipleMints {
-            amounts,
-            m
-        }.try_to_vec().unwrap()
-    );
+    setup_plan(
+        &mut banks_client,
+        &program_id,
+        &authority,
+        PlanAsset::Lamports,
+        vec![Pubkey::new_unique()],
+        PlanAllocation::Shares(vec![1u64]),
+        &recent_blockhash
+    ).await;
 
-    // provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_2.pubkey(), false)
-    ];
+    // the impostor signs for the plan of the authority
+    let mut ix = update_plan(
+        &program_id,
+        &impostor.pubkey(),
+        0u64,
+        vec![impostor.pubkey()],
+        PlanAllocation::Shares(vec![1u64])
+    ).unwrap();
+    ix.accounts[1] = AccountMeta::new(get_plan_address(&program_id, &authority.pubkey(), 0u64).0, false);
 
-    let ix = Instruction {
-        program_id,
-        data,
-        accounts
-    };
     let tx = Transaction::new_signed_with_payer(
         &[ix],
-        Some(&owner.pubkey()),
-        &[&owner],
+        Some(&authority.pubkey()),
+        &[&authority, &impostor],
         recent_blockhash
     );
 
-    banks_client
-        .process_transaction(tx)
-        .await
-        .unwrap();
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPlanAuthority
+    ).await;
+}
 
-    let source_token_account_mint_1 = TokenAccount::unpack(
-        banks_client
-            .get_account(src_token_account_mint_1.pubkey())
-            .await
-            .unwrap()
-            .unwrap()
-            .data
-            .as_slice()
-    ).unwrap();
-    let source_token_account_mint_2 = TokenAccount::unpack(
-        banks_client
-            .get_account(src_token_account_mint_2.pubkey())
-            .await
-            .unwrap()
-            .unwrap()
-            .data
-            .as_slice()
-    ).unwrap();
-    let destination_token_account_mint_1 = TokenAccount::unpack(
-        banks_client
-            .get_account(dst_token_account_mint_1.pubkey())
-            .await
-            .unwrap()
-            .unwrap()
-            .data
-            .as_slice()
-    ).unwrap();
-    let destination_token_account_mint_2 = TokenAccount::unpack(
-        banks_client
-            .get_account(dst_token_account_mint_2.pubkey())
-            .await
-            .unwrap()
-            .unwrap()
-            .data
-            .as_slice()
+#[tokio::test]
+async fn fail_createplan_invalid_plan() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let authority = payer;
+
+    // an address that is not the plan PDA of the authority and id
+    let mut ix = create_plan(
+        &program_id,
+        &authority.pubkey(),
+        &authority.pubkey(),
+        0u64,
+        PlanAsset::Lamports,
+        vec![Pubkey::new_unique()],
+        PlanAllocation::Shares(vec![1u64])
     ).unwrap();
+    ix.accounts[2] = AccountMeta::new(get_plan_address(&program_id, &authority.pubkey(), 1u64).0, false);
 
-    assert_eq!(
-        source_token_account_mint_1.amount,
-        (1000_000 - 100_000) as u64,
-        "Source token account mint-1 token balance mismatch."
-    );
-    assert_eq!(
-        source_token_account_mint_2.amount,
-        (500_00 - 50_00) as u64,
-        "Source token account mint-2 token balance mismatch."
-    );
-    assert_eq!(
-        destination_token_account_mint_1.amount,
-        100_000u64,
-        "Destination token account mint-1 token balance mismatch."
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
     );
-    assert_eq!(
-        destination_token_account_mint_2.amount,
-        50_00u64,
-        "Destination token account mint-2 token balance mismatch."
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPlan
+    ).await;
+}
+
+#[tokio::test]
+async fn fail_createplan_too_many_recipients() {
+    let program_id = Pubkey::new_unique();
+    let pt = setup(&program_id).await;
+    let (
+        mut banks_client,
+        payer,
+        recent_blockhash
+    ) = pt.start().await;
+
+    let authority = payer;
+    let recipients = (0..=MAX_PLAN_RECIPIENTS)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<Pubkey>>();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_plan(
+                &program_id,
+                &authority.pubkey(),
+                &authority.pubkey(),
+                0u64,
+                PlanAsset::Lamports,
+                recipients,
+                PlanAllocation::Shares(vec![1u64; MAX_PLAN_RECIPIENTS + 1])
+            ).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
     );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::InvalidPlanRecipients
+    ).await;
 }
 
 #[tokio::test]
-async fn fail_splitefrommultiplemintaccounts() {
-    let program_id = Pubkey::new_from_array([5u8; 32]);
+async fn fail_executeplan_version_mismatch() {
+    let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
         mut banks_client,
@@ -1121,64 +8476,45 @@ async fn fail_splitefrommultiplemintaccounts() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
+    // 1. sign the execution against the first version of the plan
     let owner = payer;
-    let mint_account_1 = Keypair::new();
-    let mint_account_2 = Keypair::new();
-    let src_token_account_mint_1 = Keypair::new();
-    let src_token_account_mint_2 = Keypair::new();
-    let dst_token_account_mint_1 = Keypair::new();
-    let dst_token_account_mint_2 = Keypair::new();
-    
-    setup_multiple_mints(
+
+    let plan = setup_plan(
         &mut banks_client,
-        &mint_account_1,
-        &mint_account_2,
-        &src_token_account_mint_1,
-        &src_token_account_mint_2,
-        &dst_token_account_mint_1,
-        &dst_token_account_mint_2,
+        &program_id,
         &owner,
+        PlanAsset::Lamports,
+        vec![Pubkey::new_unique()],
+        PlanAllocation::Amounts(vec![sol_to_lamports(1.0)]),
         &recent_blockhash
     ).await;
 
-    // 2. Create instruction
-    // provide data
-    let mut data: Vec<u8> = Vec::new();
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
-    );
-
-    let amounts: Vec<u64> = vec![
-        100_000u64,
-        50_00u64
-    ];
-    let m = 1u16;
-
-    data.append(
-        &mut SplitSplTokensFromMultipleMints {
-            amounts,
-            m
-        }.try_to_vec().unwrap()
+    let execute_tx = Transaction::new_signed_with_payer(
+        &[
+            execute_lamports_plan(
+                &program_id,
+                &owner.pubkey(),
+                &plan,
+                0u64,
+                LamportSplitOptions::default()
+            ).unwrap()
+        ],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash
     );
 
-    // provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_2.pubkey(), false)
-    ];
-
-    let ix = Instruction {
-        program_id,
-        data,
-        accounts
-    };
+    // 2. the plan is updated in the meantime
     let tx = Transaction::new_signed_with_payer(
-        &[ix],
+        &[
+            update_plan(
+                &program_id,
+                &owner.pubkey(),
+                0u64,
+                plan.recipients.clone(),
+                PlanAllocation::Amounts(vec![sol_to_lamports(2.0)])
+            ).unwrap()
+        ],
         Some(&owner.pubkey()),
         &[&owner],
         recent_blockhash
@@ -1187,12 +8523,19 @@ async fn fail_splitefrommultiplemintaccounts() {
     banks_client
         .process_transaction(tx)
         .await
-        .unwrap_err();
+        .unwrap();
+
+    // 3. the stale execution fails
+    assert_splitter_error(
+        &mut banks_client,
+        execute_tx,
+        SplitterError::PlanVersionMismatch
+    ).await;
 }
 
 #[tokio::test]
-async fn fail_splitefrommultiplemintaccounts_2() {
-    let program_id = Pubkey::new_from_array([5u8; 32]);
+async fn fail_updateplan_locked() {
+    let program_id = Pubkey::new_unique();
     let pt = setup(&program_id).await;
     let (
         mut banks_client,
@@ -1200,72 +8543,83 @@ async fn fail_splitefrommultiplemintaccounts_2() {
         recent_blockhash
     ) = pt.start().await;
 
-    // 1. setup all mint and token-accounts
-    let owner = payer;
-    let mint_account_1 = Keypair::new();
-    let mint_account_2 = Keypair::new();
-    let src_token_account_mint_1 = Keypair::new();
-    let src_token_account_mint_2 = Keypair::new();
-    let dst_token_account_mint_1 = Keypair::new();
-    let dst_token_account_mint_2 = Keypair::new();
-    
-    setup_multiple_mints(
+    // 1. lock the plan
+    let authority = payer;
+
+    setup_plan(
         &mut banks_client,
-        &mint_account_1,
-        &mint_account_2,
-        &src_token_account_mint_1,
-        &src_token_account_mint_2,
-        &dst_token_account_mint_1,
-        &dst_token_account_mint_2,
-        &owner,
+        &program_id,
+        &authority,
+        PlanAsset::Lamports,
+        vec![Pubkey::new_unique()],
+        PlanAllocation::Shares(vec![1u64]),
         &recent_blockhash
     ).await;
 
-    // 2. Create instruction
-    // provide data
-    let mut data: Vec<u8> = Vec::new();
-    data.extend_from_slice(
-        SplitterInstruction::get_discriminator("instruction:splitspltokensfrommultiplemints").as_slice()
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            lock_plan(&program_id, &authority.pubkey(), 0u64).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
     );
 
-    let amounts: Vec<u64> = vec![
-        100_000u64,
-        50_00u64,
-        100_00u64
-    ];
-    let m = 2u16;
+    banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
 
-    data.append(
-        &mut SplitSplTokensFromMultipleMints {
-            amounts,
-            m
-        }.try_to_vec().unwrap()
+    let plan = get_plan(&mut banks_client, &program_id, &authority.pubkey()).await;
+    assert!(
+        plan.locked,
+        "Plan not locked."
+    );
+    assert_eq!(
+        plan.version,
+        1u64,
+        "Version mismatch."
     );
 
-    // provide accounts
-    let accounts: Vec<AccountMeta> = vec![
-        AccountMeta::new_readonly(owner.pubkey(), true),
-        AccountMeta::new_readonly(TOKEN_STANDARD_PROGRAM, false),
-        AccountMeta::new(src_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(src_token_account_mint_2.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_1.pubkey(), false),
-        AccountMeta::new(dst_token_account_mint_2.pubkey(), false)
-    ];
+    // 2. it can neither be updated nor closed
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            update_plan(
+                &program_id,
+                &authority.pubkey(),
+                0u64,
+                vec![authority.pubkey()],
+                PlanAllocation::Shares(vec![1u64])
+            ).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[&authority],
+        recent_blockhash
+    );
+
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PlanLocked
+    ).await;
 
-    let ix = Instruction {
-        program_id,
-        data,
-        accounts
-    };
     let tx = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&owner.pubkey()),
-        &[&owner],
+        &[
+            close_plan(
+                &program_id,
+                &authority.pubkey(),
+                0u64,
+                &authority.pubkey()
+            ).unwrap()
+        ],
+        Some(&authority.pubkey()),
+        &[&authority],
         recent_blockhash
     );
 
-    banks_client
-        .process_transaction(tx)
-        .await
-        .unwrap_err();
-}
\ No newline at end of file
+    assert_splitter_error(
+        &mut banks_client,
+        tx,
+        SplitterError::PlanLocked
+    ).await;
+}